@@ -0,0 +1,172 @@
+use bech32::FromBase32;
+use sha3::{Digest, Keccak256};
+
+pub const ADDRESS_LEN: usize = 32;
+pub const HASH_LEN: usize = 32;
+const ADDRESS_HRP: &str = "erd";
+
+pub type Address = [u8; ADDRESS_LEN];
+pub type Hash = [u8; HASH_LEN];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WhitelistEntry {
+    pub address: Address,
+    pub allowance: u64,
+}
+
+/// Leaves are keccak256(address | allowance as big endian u64), same as the nft-minter contract.
+/// An allowance of 0 means the address may mint without a whitelist limit.
+pub fn leaf_hash(entry: &WhitelistEntry) -> Hash {
+    let mut hasher = Keccak256::new();
+    hasher.update(entry.address);
+    hasher.update(entry.allowance.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Pairs are sorted before hashing, so proofs do not need to encode the leaf position
+pub fn hash_pair(first: &Hash, second: &Hash) -> Hash {
+    let (left, right) = if first <= second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+
+    let mut hasher = Keccak256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+pub fn verify_proof(leaf: &Hash, proof: &[Hash], root: &Hash) -> bool {
+    let computed_root = proof
+        .iter()
+        .fold(*leaf, |current, sibling| hash_pair(&current, sibling));
+
+    &computed_root == root
+}
+
+pub struct MerkleTree {
+    layers: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// A node without a sibling is moved up to the next layer as-is
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        assert!(!leaves.is_empty(), "Cannot build a tree without leaves");
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next_layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next_layer);
+        }
+
+        MerkleTree { layers }
+    }
+
+    pub fn from_entries(entries: &[WhitelistEntry]) -> Self {
+        Self::new(entries.iter().map(leaf_hash).collect())
+    }
+
+    pub fn root(&self) -> Hash {
+        self.layers.last().unwrap()[0]
+    }
+
+    pub fn proof(&self, leaf_index: usize) -> Vec<Hash> {
+        let mut proof = Vec::new();
+        let mut index = leaf_index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling_index = index ^ 1;
+            if sibling_index < layer.len() {
+                proof.push(layer[sibling_index]);
+            }
+
+            index /= 2;
+        }
+
+        proof
+    }
+}
+
+/// Accepts either a bech32 `erd1...` address or 64 hex characters
+pub fn parse_address(input: &str) -> Result<Address, String> {
+    let input = input.trim();
+    let bytes = if input.starts_with(ADDRESS_HRP) {
+        let (hrp, data, _) = bech32::decode(input).map_err(|err| err.to_string())?;
+        if hrp != ADDRESS_HRP {
+            return Err(format!("Invalid address prefix: {}", hrp));
+        }
+
+        Vec::<u8>::from_base32(&data).map_err(|err| err.to_string())?
+    } else {
+        hex::decode(input).map_err(|err| err.to_string())?
+    };
+
+    if bytes.len() != ADDRESS_LEN {
+        return Err(format!("Invalid address length: {}", input));
+    }
+
+    let mut address = [0u8; ADDRESS_LEN];
+    address.copy_from_slice(&bytes);
+
+    Ok(address)
+}
+
+/// One `address[,allowance]` entry per line. Empty lines and lines starting with `#` are skipped.
+pub fn parse_csv(content: &str) -> Result<Vec<WhitelistEntry>, String> {
+    let mut entries = Vec::new();
+    for (line_index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut columns = line.split(',');
+        let address = parse_address(columns.next().unwrap_or_default())
+            .map_err(|err| format!("Line {}: {}", line_index + 1, err))?;
+        let allowance = match columns.next().map(str::trim) {
+            Some(value) if !value.is_empty() => value
+                .parse()
+                .map_err(|_| format!("Line {}: invalid allowance", line_index + 1))?,
+            _ => 0,
+        };
+
+        entries.push(WhitelistEntry { address, allowance });
+    }
+
+    Ok(entries)
+}
+
+pub fn to_json(entries: &[WhitelistEntry], tree: &MerkleTree) -> String {
+    let mut json = format!(
+        "{{\n  \"root\": \"{}\",\n  \"entries\": [",
+        hex::encode(tree.root())
+    );
+    for (i, entry) in entries.iter().enumerate() {
+        let proof = tree
+            .proof(i)
+            .iter()
+            .map(|hash| format!("\"{}\"", hex::encode(hash)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let separator = if i + 1 < entries.len() { "," } else { "" };
+        json += &format!(
+            "\n    {{ \"address\": \"{}\", \"allowance\": {}, \"proof\": [{}] }}{}",
+            hex::encode(entry.address),
+            entry.allowance,
+            proof,
+            separator
+        );
+    }
+    json += "\n  ]\n}\n";
+
+    json
+}
@@ -0,0 +1,34 @@
+use std::{env, fs, process};
+
+use merkle_whitelist_builder::{parse_csv, to_json, MerkleTree};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 || args.len() > 3 {
+        eprintln!("Usage: {} <whitelist.csv> [output.json]", args[0]);
+        process::exit(1);
+    }
+
+    let content = fs::read_to_string(&args[1]).unwrap_or_else(|err| {
+        eprintln!("Could not read {}: {}", args[1], err);
+        process::exit(1);
+    });
+    let entries = parse_csv(&content).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    if entries.is_empty() {
+        eprintln!("No addresses found in {}", args[1]);
+        process::exit(1);
+    }
+
+    let tree = MerkleTree::from_entries(&entries);
+    let json = to_json(&entries, &tree);
+    match args.get(2) {
+        Some(output_path) => fs::write(output_path, json).unwrap_or_else(|err| {
+            eprintln!("Could not write {}: {}", output_path, err);
+            process::exit(1);
+        }),
+        None => print!("{}", json),
+    }
+}
@@ -0,0 +1,70 @@
+use bech32::ToBase32;
+use merkle_whitelist_builder::*;
+
+fn build_entries(nr_entries: u8) -> Vec<WhitelistEntry> {
+    (0..nr_entries)
+        .map(|i| WhitelistEntry {
+            address: [i; ADDRESS_LEN],
+            allowance: i as u64,
+        })
+        .collect()
+}
+
+#[test]
+fn proofs_verify_test() {
+    for nr_entries in 1..=9 {
+        let entries = build_entries(nr_entries);
+        let tree = MerkleTree::from_entries(&entries);
+        let root = tree.root();
+
+        for (i, entry) in entries.iter().enumerate() {
+            let leaf = leaf_hash(entry);
+            assert!(verify_proof(&leaf, &tree.proof(i), &root));
+
+            let wrong_allowance_leaf = leaf_hash(&WhitelistEntry {
+                address: entry.address,
+                allowance: entry.allowance + 1,
+            });
+            assert!(!verify_proof(&wrong_allowance_leaf, &tree.proof(i), &root));
+        }
+    }
+}
+
+#[test]
+fn single_entry_tree_test() {
+    let entries = build_entries(1);
+    let tree = MerkleTree::from_entries(&entries);
+
+    assert_eq!(tree.root(), leaf_hash(&entries[0]));
+    assert!(tree.proof(0).is_empty());
+}
+
+#[test]
+fn parse_csv_test() {
+    let address = [7u8; ADDRESS_LEN];
+    let bech32_address =
+        bech32::encode("erd", address.to_base32(), bech32::Variant::Bech32).unwrap();
+    let hex_address = hex::encode([8u8; ADDRESS_LEN]);
+
+    let content = format!(
+        "# address,allowance\n{},3\n\n{}\n",
+        bech32_address, hex_address
+    );
+    let entries = parse_csv(&content).unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            WhitelistEntry {
+                address,
+                allowance: 3,
+            },
+            WhitelistEntry {
+                address: [8u8; ADDRESS_LEN],
+                allowance: 0,
+            },
+        ]
+    );
+
+    assert!(parse_csv("erd1invalid,1").is_err());
+    assert!(parse_csv(&format!("{},abc", hex_address)).is_err());
+}
@@ -3,16 +3,28 @@ elrond_wasm::derive_imports!();
 
 use crate::{
     common_storage::{BrandId, BrandInfo, MintPrice, TimePeriod},
-    nft_attributes_builder::{CollectionHash, Tag},
-    nft_tier::{TierName, MAX_TIERS_PER_BRAND},
+    nft_attributes_builder::{CollectionHash, Tag, UriTemplate, UriTemplates},
+    nft_tier::{TierExtension, TierName, MAX_EXTENSIONS_PER_TIER, MAX_TIERS_PER_BRAND},
 };
 
 const NFT_ISSUE_COST: u64 = 50_000_000_000_000_000; // 0.05 EGLD
 const ROYALTIES_MAX: u32 = 10_000; // 100%
 
 const MAX_BRAND_ID_LEN: usize = 50;
+const CLEAR_WHITELIST_GAS_RESERVE: u64 = 5_000_000;
+const CLEAR_WHITELIST_GAS_PER_ADDRESS: u64 = 2_000_000;
 pub static INVALID_BRAND_ID_ERR_MSG: &[u8] = b"Invalid Brand ID";
 pub static INVALID_TIER_ERR_MSG: &[u8] = b"Invalid tier";
+pub static EXTRA_PRICE_OPTIONS_CONFLICT_ERR_MSG: &[u8] =
+    b"Extra price options may not be combined with a pricing strategy or phase prices";
+
+static MINT_PERIOD_FIELD: &[u8] = b"mintPeriod";
+static PRICE_FIELD: &[u8] = b"price";
+static TAGS_FIELD: &[u8] = b"tags";
+static ROYALTIES_FIELD: &[u8] = b"royalties";
+static TOKEN_DISPLAY_NAME_FIELD: &[u8] = b"tokenDisplayName";
+static TIERS_FIELD: &[u8] = b"tiers";
+static URI_TEMPLATES_FIELD: &[u8] = b"uriTemplates";
 
 #[derive(TopEncode, TopDecode, NestedEncode, NestedDecode)]
 pub struct TempCallbackTierInfo<M: ManagedTypeApi> {
@@ -20,17 +32,30 @@ pub struct TempCallbackTierInfo<M: ManagedTypeApi> {
     pub total_nfts: usize,
     pub id_offset: usize,
     pub mint_price: MintPrice<M>,
+    pub extra_price_options: ManagedVec<M, MintPrice<M>>,
+    pub max_nfts_per_wallet: usize,
 }
 
 #[derive(TopEncode, TopDecode)]
 pub struct TempCallbackStorageInfo<M: ManagedTypeApi> {
+    pub original_caller: ManagedAddress<M>,
     pub brand_info: BrandInfo<M>,
+    pub max_nfts_per_wallet: usize,
+    pub opt_uri_templates: Option<UriTemplates<M>>,
     pub tags: ManagedVec<M, Tag<M>>,
     pub tier_info_entries: ArrayVec<TempCallbackTierInfo<M>, MAX_TIERS_PER_BRAND>,
 }
 
-/// Tier name, number of NFTs, price
-pub type TierArgPair<M> = MultiValue3<TierName<M>, usize, BigUint<M>>;
+/// Tier name, number of NFTs, price, max NFTs per wallet (0 for no limit),
+/// prices in other accepted tokens
+pub type TierArgPair<M> =
+    MultiValue5<TierName<M>, usize, BigUint<M>, usize, ManagedVec<M, MintPrice<M>>>;
+
+/// Address, max NFTs it may mint while the whitelist is required (0 for no limit)
+pub type WhitelistQuotaArg<M> = MultiValue2<ManagedAddress<M>, usize>;
+
+/// Token, price per NFT
+pub type PriceOptionArg<M> = MultiValue2<EgldOrEsdtTokenIdentifier<M>, BigUint<M>>;
 
 #[elrond_wasm::module]
 pub trait BrandCreationModule:
@@ -38,8 +63,15 @@ pub trait BrandCreationModule:
     + crate::common_storage::CommonStorageModule
     + crate::nft_attributes_builder::NftAttributesBuilderModule
     + crate::nft_tier::NftTierModule
+    + crate::mint_phases::MintPhasesModule
+    + crate::wallet_limits::WalletLimitsModule
+    + crate::pause::PauseModule
+    + crate::pricing::PricingModule
+    + crate::royalties::RoyaltiesModule
     + crate::events::EventsModule
 {
+    /// With `delayed_reveal`, `collection_hash` is a placeholder used until `revealBrand` is called.
+    /// Empty URI templates mean the brand uses the contract-wide ones.
     #[payable("EGLD")]
     #[endpoint(issueTokenForBrand)]
     fn issue_token_for_brand(
@@ -54,23 +86,178 @@ pub trait BrandCreationModule:
         token_display_name: ManagedBuffer,
         token_ticker: ManagedBuffer,
         whitelist_expire_timestamp: u64,
+        max_nfts_per_wallet: usize,
+        delayed_reveal: bool,
+        file_uri_template: UriTemplate<Self::Api>,
+        metadata_template: UriTemplate<Self::Api>,
         tags: ManagedVec<Tag<Self::Api>>,
         tier_name_nr_nfts_pairs: MultiValueEncoded<TierArgPair<Self::Api>>,
     ) {
         self.require_caller_is_admin();
 
+        let payment_amount = self.call_value().egld_value();
+        require!(
+            payment_amount == NFT_ISSUE_COST,
+            "Invalid payment amount. Issue costs exactly 0.05 EGLD"
+        );
+
+        let brand_creation_info = self.register_brand(
+            collection_hash.clone(),
+            brand_id.clone(),
+            media_type,
+            royalties,
+            TimePeriod {
+                start: mint_start_timestamp,
+                end: mint_end_timestamp,
+            },
+            mint_price_token_id,
+            token_display_name.clone(),
+            whitelist_expire_timestamp,
+            max_nfts_per_wallet,
+            delayed_reveal,
+            file_uri_template,
+            metadata_template,
+            tags,
+            tier_name_nr_nfts_pairs,
+        );
+        let issue_id = self.last_issue_id().update(|last_id| {
+            *last_id += 1;
+            *last_id
+        });
+        let original_caller = brand_creation_info.original_caller.clone();
+        self.temporary_callback_storage(&brand_id)
+            .set(&brand_creation_info);
+        self.pending_issue_id(&brand_id).set(issue_id);
+
+        self.nft_token(&brand_id).issue_and_set_all_roles(
+            EsdtTokenType::NonFungible,
+            payment_amount,
+            token_display_name,
+            token_ticker,
+            0,
+            Some(self.callbacks().issue_callback(
+                collection_hash,
+                brand_id,
+                original_caller,
+                issue_id,
+            )),
+        );
+    }
+
+    /// Same as `issueTokenForBrand`, but uses an NFT collection the owner already has.
+    /// Minting is enabled once the roles for it are transferred to the SC and confirmed through `confirmTokenRoles`.
+    #[endpoint(registerBrandWithExistingToken)]
+    fn register_brand_with_existing_token(
+        &self,
+        nft_token_id: TokenIdentifier,
+        collection_hash: CollectionHash<Self::Api>,
+        brand_id: BrandId<Self::Api>,
+        media_type: ManagedBuffer,
+        royalties: BigUint,
+        mint_start_timestamp: u64,
+        mint_end_timestamp: u64,
+        mint_price_token_id: EgldOrEsdtTokenIdentifier,
+        token_display_name: ManagedBuffer,
+        whitelist_expire_timestamp: u64,
+        max_nfts_per_wallet: usize,
+        delayed_reveal: bool,
+        file_uri_template: UriTemplate<Self::Api>,
+        metadata_template: UriTemplate<Self::Api>,
+        tags: ManagedVec<Tag<Self::Api>>,
+        tier_name_nr_nfts_pairs: MultiValueEncoded<TierArgPair<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(nft_token_id.is_valid_esdt_identifier(), "Invalid token ID");
+        require!(
+            self.brand_id_for_token(&nft_token_id).is_empty(),
+            "Token already used by another brand"
+        );
+
+        let brand_creation_info = self.register_brand(
+            collection_hash,
+            brand_id.clone(),
+            media_type,
+            royalties,
+            TimePeriod {
+                start: mint_start_timestamp,
+                end: mint_end_timestamp,
+            },
+            mint_price_token_id,
+            token_display_name,
+            whitelist_expire_timestamp,
+            max_nfts_per_wallet,
+            delayed_reveal,
+            file_uri_template,
+            metadata_template,
+            tags,
+            tier_name_nr_nfts_pairs,
+        );
+        self.store_brand(&brand_id, &nft_token_id, brand_creation_info);
+        self.needs_token_role_confirmation(&brand_id).set(true);
+    }
+
+    /// Checks the SC's roles for a brand registered with an existing token once, instead of on every mint.
+    /// `ESDTRoleNFTCreate` is always required, while unrevealed brands
+    /// also need `ESDTRoleNFTUpdateAttributes` and `ESDTRoleNFTAddURI` for the reveal.
+    /// Tokens issued through `issueTokenForBrand` already get all roles, so they need no confirmation.
+    #[endpoint(confirmTokenRoles)]
+    fn confirm_token_roles(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        let nft_token_mapper = self.nft_token(&brand_id);
+        require!(!nft_token_mapper.is_empty(), "Token not issued yet");
+
+        let roles = self
+            .blockchain()
+            .get_esdt_local_roles(&nft_token_mapper.get_token_id());
+        require!(
+            roles.has_role(&EsdtLocalRole::NftCreate),
+            "NFT create role not set"
+        );
+
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        if !brand_info.is_revealed {
+            require!(
+                roles.has_role(&EsdtLocalRole::NftUpdateAttributes)
+                    && roles.has_role(&EsdtLocalRole::NftAddUri),
+                "NFT update attributes and add URI roles not set"
+            );
+        }
+
+        self.needs_token_role_confirmation(&brand_id).clear();
+    }
+
+    fn register_brand(
+        &self,
+        collection_hash: CollectionHash<Self::Api>,
+        brand_id: BrandId<Self::Api>,
+        media_type: ManagedBuffer,
+        royalties: BigUint,
+        mint_period: TimePeriod,
+        mint_price_token_id: EgldOrEsdtTokenIdentifier,
+        token_display_name: ManagedBuffer,
+        whitelist_expire_timestamp: u64,
+        max_nfts_per_wallet: usize,
+        delayed_reveal: bool,
+        file_uri_template: UriTemplate<Self::Api>,
+        metadata_template: UriTemplate<Self::Api>,
+        tags: ManagedVec<Tag<Self::Api>>,
+        tier_name_nr_nfts_pairs: MultiValueEncoded<TierArgPair<Self::Api>>,
+    ) -> TempCallbackStorageInfo<Self::Api> {
         let id_len = brand_id.len();
         require!(
             id_len > 0 && id_len <= MAX_BRAND_ID_LEN,
             INVALID_BRAND_ID_ERR_MSG
         );
 
-        let payment_amount = self.call_value().egld_value();
         require!(
-            payment_amount == NFT_ISSUE_COST,
-            "Invalid payment amount. Issue costs exactly 0.05 EGLD"
+            self.is_valid_collection_hash(&collection_hash),
+            "Invalid collection hash"
         );
-
         require!(
             self.is_supported_media_type(&media_type),
             "Invalid media type"
@@ -78,18 +265,19 @@ pub trait BrandCreationModule:
         require!(royalties <= ROYALTIES_MAX, "Royalties cannot be over 100%");
         require!(mint_price_token_id.is_valid(), "Invalid price token");
 
-        let is_new_collection = self
-            .registered_collection_hashes()
-            .insert(collection_hash.clone());
+        let opt_uri_templates = if file_uri_template.is_empty() && metadata_template.is_empty() {
+            None
+        } else {
+            Some(self.build_uri_templates(file_uri_template, metadata_template))
+        };
+
+        let is_new_collection = self.register_collection_hash(&collection_hash);
         require!(is_new_collection, "Collection hash already exists");
 
         let is_new_brand = self.registered_brands().insert(brand_id.clone());
         require!(is_new_brand, "Brand already exists");
 
-        require!(
-            mint_start_timestamp < mint_end_timestamp,
-            "Invalid timestamps"
-        );
+        require!(mint_period.start < mint_period.end, "Invalid timestamps");
         require!(
             !tier_name_nr_nfts_pairs.is_empty(),
             "Must have at least one tier"
@@ -103,10 +291,17 @@ pub trait BrandCreationModule:
         let mut tiers_info = ArrayVec::new();
         let mut current_id_offset = 0;
         for pair in tier_name_nr_nfts_pairs {
-            let (tier, nr_nfts, price): (TierName<Self::Api>, usize, BigUint) = pair.into_tuple();
+            let (tier, nr_nfts, price, tier_max_nfts_per_wallet, extra_price_options): (
+                TierName<Self::Api>,
+                usize,
+                BigUint,
+                usize,
+                ManagedVec<MintPrice<Self::Api>>,
+            ) = pair.into_tuple();
 
             let is_new_tier = tier_mapper.insert(tier.clone());
             require!(is_new_tier, "Duplicate tier name");
+            self.require_valid_extra_price_options(&mint_price_token_id, &extra_price_options);
 
             unsafe {
                 tiers_info.push_unchecked(TempCallbackTierInfo {
@@ -117,97 +312,175 @@ pub trait BrandCreationModule:
                         token_id: mint_price_token_id.clone(),
                         amount: price,
                     },
+                    extra_price_options,
+                    max_nfts_per_wallet: tier_max_nfts_per_wallet,
                 });
             }
             current_id_offset += nr_nfts;
         }
 
         let brand_info = BrandInfo {
-            collection_hash: collection_hash.clone(),
-            token_display_name: token_display_name.clone(),
+            collection_hash,
+            token_display_name,
             media_type,
             royalties,
-            mint_period: TimePeriod {
-                start: mint_start_timestamp,
-                end: mint_end_timestamp,
-            },
+            mint_period,
             whitelist_expire_timestamp,
+            is_revealed: !delayed_reveal,
         };
 
-        self.temporary_callback_storage(&brand_id)
-            .set(&TempCallbackStorageInfo {
-                brand_info,
-                tags,
-                tier_info_entries: tiers_info,
-            });
-
-        self.nft_token(&brand_id).issue_and_set_all_roles(
-            EsdtTokenType::NonFungible,
-            payment_amount,
-            token_display_name,
-            token_ticker,
-            0,
-            Some(self.callbacks().issue_callback(collection_hash, brand_id)),
-        );
+        TempCallbackStorageInfo {
+            original_caller: self.blockchain().get_caller(),
+            brand_info,
+            max_nfts_per_wallet,
+            opt_uri_templates,
+            tags,
+            tier_info_entries: tiers_info,
+        }
     }
 
+    /// A callback for an issue whose brand creation was cleared through `clearPendingBrandCreation`
+    /// doesn't touch the brand, which may have been created again since.
     #[callback]
     fn issue_callback(
         &self,
         collection_hash: CollectionHash<Self::Api>,
         brand_id: BrandId<Self::Api>,
+        original_caller: ManagedAddress,
+        issue_id: u64,
         #[call_result] result: ManagedAsyncCallResult<TokenIdentifier>,
     ) {
+        let cb_info_mapper = self.temporary_callback_storage(&brand_id);
+        let issue_id_mapper = self.pending_issue_id(&brand_id);
+        if cb_info_mapper.is_empty() || issue_id_mapper.get() != issue_id {
+            self.handle_stale_issue_callback(&brand_id, &original_caller, result);
+            return;
+        }
+
+        let cb_info: TempCallbackStorageInfo<Self::Api> = cb_info_mapper.get();
         match result {
             ManagedAsyncCallResult::Ok(token_id) => {
-                let cb_info: TempCallbackStorageInfo<Self::Api> =
-                    self.temporary_callback_storage(&brand_id).get();
-
-                self.nft_token(&brand_id).set_token_id(&token_id);
-                self.brand_info(&brand_id).set(&cb_info.brand_info);
-
-                for tier_info in cb_info.tier_info_entries {
-                    self.available_ids(&brand_id, &tier_info.tier)
-                        .set_initial_len(tier_info.total_nfts);
-                    self.total_nfts(&brand_id, &tier_info.tier)
-                        .set(tier_info.total_nfts);
-                    self.nft_id_offset_for_tier(&brand_id, &tier_info.tier)
-                        .set(tier_info.id_offset);
-
-                    self.price_for_tier(&brand_id, &tier_info.tier)
-                        .set(&tier_info.mint_price);
+                self.store_brand(&brand_id, &token_id, cb_info);
+            }
+            ManagedAsyncCallResult::Err(err) => {
+                self.unregister_brand(&brand_id, &collection_hash);
+
+                // the issue cost is returned to the SC along with the failed callback
+                let returned_amount = self.call_value().egld_value();
+                if returned_amount > 0 {
+                    self.send()
+                        .direct_egld(&cb_info.original_caller, &returned_amount);
                 }
 
-                if !cb_info.tags.is_empty() {
-                    self.tags_for_brand(&brand_id).set(&cb_info.tags);
+                self.brand_creation_failed_event(&brand_id, &cb_info.original_caller, &err.err_msg);
+            }
+        }
+
+        cb_info_mapper.clear();
+        issue_id_mapper.clear();
+    }
+
+    fn handle_stale_issue_callback(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        original_caller: &ManagedAddress,
+        result: ManagedAsyncCallResult<TokenIdentifier>,
+    ) {
+        match result {
+            ManagedAsyncCallResult::Ok(token_id) => {
+                self.orphan_token_issued_event(brand_id, original_caller, &token_id);
+            }
+            ManagedAsyncCallResult::Err(err) => {
+                let returned_amount = self.call_value().egld_value();
+                if returned_amount > 0 {
+                    self.send().direct_egld(original_caller, &returned_amount);
                 }
 
-                self.brand_created_event(&brand_id, &token_id);
+                self.brand_creation_failed_event(brand_id, original_caller, &err.err_msg);
             }
-            ManagedAsyncCallResult::Err(_) => {
-                let _ = self.registered_brands().swap_remove(&brand_id);
-                let _ = self
-                    .registered_collection_hashes()
-                    .swap_remove(&collection_hash);
-                self.nft_tiers_for_brand(&brand_id).clear();
+        }
+    }
+
+    fn store_brand(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        token_id: &TokenIdentifier,
+        brand_creation_info: TempCallbackStorageInfo<Self::Api>,
+    ) {
+        self.nft_token(brand_id).set_token_id(token_id);
+        self.brand_id_for_token(token_id).set(brand_id);
+        self.brand_info(brand_id)
+            .set(&brand_creation_info.brand_info);
+        self.max_nfts_per_wallet(brand_id)
+            .set(brand_creation_info.max_nfts_per_wallet);
+        if let Some(uri_templates) = brand_creation_info.opt_uri_templates {
+            self.uri_templates_for_brand(brand_id).set(&uri_templates);
+        }
+
+        for tier_info in brand_creation_info.tier_info_entries {
+            self.available_ids(brand_id, &tier_info.tier)
+                .set_initial_len(tier_info.total_nfts);
+            self.total_nfts(brand_id, &tier_info.tier)
+                .set(tier_info.total_nfts);
+            self.nft_id_offset_for_tier(brand_id, &tier_info.tier)
+                .set(tier_info.id_offset);
+
+            self.price_for_tier(brand_id, &tier_info.tier)
+                .set(&tier_info.mint_price);
+            let mut extra_price_options_mapper =
+                self.extra_price_options_for_tier(brand_id, &tier_info.tier);
+            for price_option in &tier_info.extra_price_options {
+                let _ =
+                    extra_price_options_mapper.insert(price_option.token_id, price_option.amount);
             }
+            self.max_nfts_per_wallet_for_tier(brand_id, &tier_info.tier)
+                .set(tier_info.max_nfts_per_wallet);
+        }
+
+        if !brand_creation_info.tags.is_empty() {
+            self.tags_for_brand(brand_id).set(&brand_creation_info.tags);
         }
 
-        self.temporary_callback_storage(&brand_id).clear();
+        self.brand_created_event(brand_id, token_id);
     }
 
+    /// Cleans up a brand whose issue callback never arrived.
+    /// A callback arriving afterwards only refunds the issue cost if the issue failed,
+    /// or emits an `orphanTokenIssued` event otherwise.
+    #[endpoint(clearPendingBrandCreation)]
+    fn clear_pending_brand_creation(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+
+        let cb_info_mapper = self.temporary_callback_storage(&brand_id);
+        require!(!cb_info_mapper.is_empty(), "No pending brand creation");
+
+        let cb_info: TempCallbackStorageInfo<Self::Api> = cb_info_mapper.get();
+        self.unregister_brand(&brand_id, &cb_info.brand_info.collection_hash);
+        cb_info_mapper.clear();
+        self.pending_issue_id(&brand_id).clear();
+    }
+
+    fn unregister_brand(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        collection_hash: &CollectionHash<Self::Api>,
+    ) {
+        let _ = self.registered_brands().swap_remove(brand_id);
+        self.unregister_collection_hash(collection_hash);
+        self.nft_tiers_for_brand(brand_id).clear();
+    }
+
+    /// Re-adding an address changes its quota, NFTs it already minted still count towards it
     #[endpoint(addToWhitelist)]
     fn add_to_whitelist(
         &self,
         brand_id: BrandId<Self::Api>,
-        users: MultiValueEncoded<ManagedAddress>,
+        user_quota_pairs: MultiValueEncoded<WhitelistQuotaArg<Self::Api>>,
     ) {
         self.require_caller_is_admin();
 
         let mut mapper = self.mint_whitelist(&brand_id);
-        for user in users {
-            let _ = mapper.insert(user);
-        }
+        self.add_whitelist_quotas(&brand_id, &TierName::new(), &mut mapper, user_quota_pairs);
     }
 
     #[endpoint(removeFromWhitelist)]
@@ -219,8 +492,97 @@ pub trait BrandCreationModule:
         self.require_caller_is_admin();
 
         let mut mapper = self.mint_whitelist(&brand_id);
+        self.remove_whitelist_quotas(&brand_id, &TierName::new(), &mut mapper, users);
+    }
+
+    /// When buying from the tier, these addresses use their tier quota instead of the brand-wide one.
+    /// Only applies to single tier buys, multi-tier and mystery tier buys use the brand-wide whitelist.
+    #[endpoint(addToTierWhitelist)]
+    fn add_to_tier_whitelist(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        user_quota_pairs: MultiValueEncoded<WhitelistQuotaArg<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+
+        let mut mapper = self.tier_mint_whitelist(&brand_id, &tier);
+        self.add_whitelist_quotas(&brand_id, &tier, &mut mapper, user_quota_pairs);
+    }
+
+    #[endpoint(removeFromTierWhitelist)]
+    fn remove_from_tier_whitelist(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        users: MultiValueEncoded<ManagedAddress>,
+    ) {
+        self.require_caller_is_admin();
+
+        let mut mapper = self.tier_mint_whitelist(&brand_id, &tier);
+        self.remove_whitelist_quotas(&brand_id, &tier, &mut mapper, users);
+    }
+
+    /// Removes all addresses from the brand-wide whitelist, or from the tier's if given.
+    /// Must be called again as long as it returns "interrupted".
+    #[endpoint(clearWhitelist)]
+    fn clear_whitelist(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        opt_tier: OptionalValue<TierName<Self::Api>>,
+    ) -> OperationCompletionStatus {
+        self.require_caller_is_admin();
+
+        let (tier, mut whitelist_mapper) = self.get_whitelist_mapper(&brand_id, opt_tier);
+        let whitelist_len = whitelist_mapper.len();
+        let gas_left = self.blockchain().get_gas_left();
+        let max_addresses_for_gas =
+            gas_left.saturating_sub(CLEAR_WHITELIST_GAS_RESERVE) / CLEAR_WHITELIST_GAS_PER_ADDRESS;
+        let addresses_in_batch =
+            core::cmp::min(whitelist_len as u64, max_addresses_for_gas) as usize;
+
+        // removing from the end does not move any other address
+        for index in (whitelist_len - addresses_in_batch + 1..=whitelist_len).rev() {
+            let user = whitelist_mapper.get_by_index(index);
+            self.whitelist_quota(&brand_id, &tier, &user).clear();
+            let _ = whitelist_mapper.swap_remove(&user);
+        }
+
+        if addresses_in_batch < whitelist_len {
+            OperationCompletionStatus::InterruptedBeforeOutOfGas
+        } else {
+            OperationCompletionStatus::Completed
+        }
+    }
+
+    fn add_whitelist_quotas(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        whitelist_mapper: &mut UnorderedSetMapper<ManagedAddress>,
+        user_quota_pairs: MultiValueEncoded<WhitelistQuotaArg<Self::Api>>,
+    ) {
+        for pair in user_quota_pairs {
+            let (user, quota) = pair.into_tuple();
+            self.whitelist_quota(brand_id, tier, &user).set(quota);
+            let _ = whitelist_mapper.insert(user);
+        }
+    }
+
+    fn remove_whitelist_quotas(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        whitelist_mapper: &mut UnorderedSetMapper<ManagedAddress>,
+        users: MultiValueEncoded<ManagedAddress>,
+    ) {
         for user in users {
-            let _ = mapper.swap_remove(&user);
+            self.whitelist_quota(brand_id, tier, &user).clear();
+            let _ = whitelist_mapper.swap_remove(&user);
         }
     }
 
@@ -232,9 +594,452 @@ pub trait BrandCreationModule:
             .update(|info| info.whitelist_expire_timestamp = timestamp);
     }
 
+    /// The start may not be moved once the mint started,
+    /// and existing mint phases must still fit in the new period
+    #[endpoint(setMintPeriod)]
+    fn set_mint_period(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        mint_start_timestamp: u64,
+        mint_end_timestamp: u64,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            mint_start_timestamp < mint_end_timestamp,
+            "Invalid timestamps"
+        );
+
+        let brand_info_mapper = self.brand_info(&brand_id);
+        let mut brand_info: BrandInfo<Self::Api> = brand_info_mapper.get();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            current_timestamp < brand_info.mint_period.start
+                || mint_start_timestamp == brand_info.mint_period.start,
+            "May not change start after mint started"
+        );
+
+        for phase in self.mint_phases(&brand_id).iter() {
+            require!(
+                phase.period.start >= mint_start_timestamp
+                    && phase.period.end <= mint_end_timestamp,
+                "Phase must be within the mint period"
+            );
+        }
+
+        brand_info.mint_period = TimePeriod {
+            start: mint_start_timestamp,
+            end: mint_end_timestamp,
+        };
+        brand_info_mapper.set(&brand_info);
+
+        self.brand_updated_event(&brand_id, &ManagedBuffer::new_from_bytes(MINT_PERIOD_FIELD));
+    }
+
+    /// During the mint, the tier must be paused first.
+    /// The price token may only change while the tier has no phase prices or pricing strategy,
+    /// as their amounts are given in the current token.
+    #[endpoint(setPriceForTier)]
+    fn set_price_for_tier(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        price_token_id: EgldOrEsdtTokenIdentifier,
+        price_amount: BigUint,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+        require!(price_token_id.is_valid(), "Invalid price token");
+        require!(
+            !self
+                .extra_price_options_for_tier(&brand_id, &tier)
+                .contains_key(&price_token_id),
+            "Duplicate price token"
+        );
+        self.require_price_change_allowed(&brand_id, &tier);
+
+        let price_mapper = self.price_for_tier(&brand_id, &tier);
+        if price_mapper.get().token_id != price_token_id {
+            require!(
+                self.pricing_strategy_for_tier(&brand_id, &tier).is_empty()
+                    && !self.has_any_phase_price(&brand_id, &tier),
+                "May not change price token with phase prices or a pricing strategy"
+            );
+        }
+
+        price_mapper.set(&MintPrice {
+            token_id: price_token_id,
+            amount: price_amount,
+        });
+
+        self.brand_updated_event(&brand_id, &ManagedBuffer::new_from_bytes(PRICE_FIELD));
+    }
+
+    /// Replaces the tokens accepted besides the tier's price token.
+    /// These are fixed prices per NFT, so the tier may not also have a pricing strategy or phase prices.
+    #[endpoint(setExtraPriceOptionsForTier)]
+    fn set_extra_price_options_for_tier(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        price_options: MultiValueEncoded<PriceOptionArg<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+        self.require_price_change_allowed(&brand_id, &tier);
+
+        let mut extra_price_options = ManagedVec::new();
+        for price_option in price_options {
+            let (token_id, amount) = price_option.into_tuple();
+            extra_price_options.push(MintPrice { token_id, amount });
+        }
+
+        let primary_token_id = self.price_for_tier(&brand_id, &tier).get().token_id;
+        self.require_valid_extra_price_options(&primary_token_id, &extra_price_options);
+        if !extra_price_options.is_empty() {
+            require!(
+                self.pricing_strategy_for_tier(&brand_id, &tier).is_empty()
+                    && !self.has_any_phase_price(&brand_id, &tier),
+                EXTRA_PRICE_OPTIONS_CONFLICT_ERR_MSG
+            );
+        }
+
+        let mut mapper = self.extra_price_options_for_tier(&brand_id, &tier);
+        mapper.clear();
+        for price_option in &extra_price_options {
+            let _ = mapper.insert(price_option.token_id, price_option.amount);
+        }
+
+        self.brand_updated_event(&brand_id, &ManagedBuffer::new_from_bytes(PRICE_FIELD));
+    }
+
+    fn require_price_change_allowed(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) {
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(brand_id).get();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let is_mint_active = current_timestamp >= brand_info.mint_period.start
+            && current_timestamp < brand_info.mint_period.end;
+        require!(
+            !is_mint_active || self.is_tier_paused(brand_id, tier),
+            "May not change price during mint unless paused"
+        );
+        require!(
+            self.auction_total_sold(brand_id, tier).get() == 0,
+            "May not change pricing after auction sales"
+        );
+    }
+
+    fn require_valid_extra_price_options(
+        &self,
+        primary_token_id: &EgldOrEsdtTokenIdentifier,
+        extra_price_options: &ManagedVec<MintPrice<Self::Api>>,
+    ) {
+        for (i, price_option) in extra_price_options.iter().enumerate() {
+            require!(price_option.token_id.is_valid(), "Invalid price token");
+            require!(
+                &price_option.token_id != primary_token_id,
+                "Duplicate price token"
+            );
+
+            for other_option in extra_price_options.iter().skip(i + 1) {
+                require!(
+                    other_option.token_id != price_option.token_id,
+                    "Duplicate price token"
+                );
+            }
+        }
+    }
+
+    /// Only affects NFTs minted or revealed afterwards
+    #[endpoint(setTagsForBrand)]
+    fn set_tags_for_brand(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tags: MultiValueEncoded<Tag<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        let tags = tags.to_vec();
+        if tags.is_empty() {
+            self.tags_for_brand(&brand_id).clear();
+        } else {
+            self.tags_for_brand(&brand_id).set(&tags);
+        }
+
+        self.brand_updated_event(&brand_id, &ManagedBuffer::new_from_bytes(TAGS_FIELD));
+    }
+
+    /// Only affects NFTs minted afterwards
+    #[endpoint(setRoyaltiesForBrand)]
+    fn set_royalties_for_brand(&self, brand_id: BrandId<Self::Api>, royalties: BigUint) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(royalties <= ROYALTIES_MAX, "Royalties cannot be over 100%");
+
+        self.brand_info(&brand_id)
+            .update(|info| info.royalties = royalties);
+
+        self.brand_updated_event(&brand_id, &ManagedBuffer::new_from_bytes(ROYALTIES_FIELD));
+    }
+
+    /// Only affects NFTs minted afterwards
+    #[endpoint(setTokenDisplayName)]
+    fn set_token_display_name(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        token_display_name: ManagedBuffer,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.brand_info(&brand_id)
+            .update(|info| info.token_display_name = token_display_name);
+
+        self.brand_updated_event(
+            &brand_id,
+            &ManagedBuffer::new_from_bytes(TOKEN_DISPLAY_NAME_FIELD),
+        );
+    }
+
+    /// The new tier's IDs are `id_offset + 1..=id_offset + nr_nfts`,
+    /// so `id_offset` may not be lower than any ID the brand already uses.
+    #[endpoint(addTierToBrand)]
+    fn add_tier_to_brand(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        nr_nfts: usize,
+        price_token_id: EgldOrEsdtTokenIdentifier,
+        price_amount: BigUint,
+        max_nfts_per_wallet: usize,
+        id_offset: usize,
+        opt_collection_hash: OptionalValue<CollectionHash<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(price_token_id.is_valid(), "Invalid price token");
+
+        let mut tier_mapper = self.nft_tiers_for_brand(&brand_id);
+        require!(
+            tier_mapper.len() < MAX_TIERS_PER_BRAND,
+            "Max tiers per brand limit exceeded"
+        );
+
+        let is_new_tier = tier_mapper.insert(tier.clone());
+        require!(is_new_tier, "Duplicate tier name");
+
+        self.price_for_tier(&brand_id, &tier).set(&MintPrice {
+            token_id: price_token_id,
+            amount: price_amount,
+        });
+        self.max_nfts_per_wallet_for_tier(&brand_id, &tier)
+            .set(max_nfts_per_wallet);
+
+        // all of the tier's IDs live in its first extension
+        self.add_tier_extension(
+            &brand_id,
+            &tier,
+            nr_nfts,
+            id_offset,
+            opt_collection_hash.into_option(),
+        );
+    }
+
+    /// Adds `nr_nfts` IDs to an existing tier, same rules as `addTierToBrand`
+    #[endpoint(extendTier)]
+    fn extend_tier(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        nr_nfts: usize,
+        id_offset: usize,
+        opt_collection_hash: OptionalValue<CollectionHash<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+
+        self.add_tier_extension(
+            &brand_id,
+            &tier,
+            nr_nfts,
+            id_offset,
+            opt_collection_hash.into_option(),
+        );
+    }
+
+    fn add_tier_extension(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        nr_nfts: usize,
+        id_offset: usize,
+        opt_collection_hash: Option<CollectionHash<Self::Api>>,
+    ) {
+        require!(nr_nfts > 0, "Must add at least one NFT");
+        require!(!self.mint_closed(brand_id).get(), "Mint closed");
+        require!(
+            id_offset >= self.get_highest_nft_id(brand_id),
+            "ID range overlaps existing IDs"
+        );
+
+        let mut extensions_mapper = self.tier_extensions(brand_id, tier);
+        require!(
+            extensions_mapper.len() < MAX_EXTENSIONS_PER_TIER,
+            "Max extensions per tier limit exceeded"
+        );
+
+        if let Some(collection_hash) = &opt_collection_hash {
+            require!(
+                self.is_valid_collection_hash(collection_hash),
+                "Invalid collection hash"
+            );
+
+            let brand_info: BrandInfo<Self::Api> = self.brand_info(brand_id).get();
+            require!(
+                brand_info.is_revealed,
+                "May not use another collection hash before reveal"
+            );
+
+            let is_new_collection = self.register_collection_hash(collection_hash);
+            require!(is_new_collection, "Collection hash already exists");
+        }
+
+        let extension_index = extensions_mapper.push(&TierExtension {
+            id_offset,
+            total_nfts: nr_nfts,
+            opt_collection_hash,
+        });
+        self.extension_available_ids(brand_id, tier, extension_index)
+            .set_initial_len(nr_nfts);
+        self.total_nfts(brand_id, tier)
+            .update(|total_nfts| *total_nfts += nr_nfts);
+
+        self.brand_updated_event(brand_id, &ManagedBuffer::new_from_bytes(TIERS_FIELD));
+    }
+
+    /// Only affects NFTs minted or revealed afterwards
+    #[endpoint(setUriTemplatesForBrand)]
+    fn set_uri_templates_for_brand(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        file_uri_template: UriTemplate<Self::Api>,
+        metadata_template: UriTemplate<Self::Api>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        let uri_templates = self.build_uri_templates(file_uri_template, metadata_template);
+        self.uri_templates_for_brand(&brand_id).set(&uri_templates);
+
+        self.brand_updated_event(
+            &brand_id,
+            &ManagedBuffer::new_from_bytes(URI_TEMPLATES_FIELD),
+        );
+    }
+
+    #[endpoint(clearUriTemplatesForBrand)]
+    fn clear_uri_templates_for_brand(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.uri_templates_for_brand(&brand_id).clear();
+
+        self.brand_updated_event(
+            &brand_id,
+            &ManagedBuffer::new_from_bytes(URI_TEMPLATES_FIELD),
+        );
+    }
+
+    /// Used by all brands without their own templates
+    #[endpoint(setDefaultUriTemplates)]
+    fn set_default_uri_templates(
+        &self,
+        file_uri_template: UriTemplate<Self::Api>,
+        metadata_template: UriTemplate<Self::Api>,
+    ) {
+        self.require_caller_is_admin();
+
+        let uri_templates = self.build_uri_templates(file_uri_template, metadata_template);
+        self.default_uri_templates().set(&uri_templates);
+
+        self.brand_updated_event(
+            &BrandId::new(),
+            &ManagedBuffer::new_from_bytes(URI_TEMPLATES_FIELD),
+        );
+    }
+
+    fn build_uri_templates(
+        &self,
+        file_uri_template: UriTemplate<Self::Api>,
+        metadata_template: UriTemplate<Self::Api>,
+    ) -> UriTemplates<Self::Api> {
+        require!(
+            self.is_valid_uri_template(&file_uri_template)
+                && self.is_valid_uri_template(&metadata_template),
+            "Invalid URI template"
+        );
+
+        UriTemplates {
+            file_uri: file_uri_template,
+            metadata: metadata_template,
+        }
+    }
+
     #[storage_mapper("temporaryCallbackStorage")]
     fn temporary_callback_storage(
         &self,
         brand_id: &BrandId<Self::Api>,
     ) -> SingleValueMapper<TempCallbackStorageInfo<Self::Api>>;
+
+    #[storage_mapper("pendingIssueId")]
+    fn pending_issue_id(&self, brand_id: &BrandId<Self::Api>) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("lastIssueId")]
+    fn last_issue_id(&self) -> SingleValueMapper<u64>;
 }
@@ -1,8 +1,11 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
+use core::convert::TryFrom;
+use elrond_wasm::elrond_codec::{DecodeErrorHandler, NestedDecodeInput, TopDecodeInput};
+
 use crate::{
-    nft_attributes_builder::{CollectionHash, MediaType, Tag},
+    nft_attributes_builder::{CollectionHash, MediaType, Tag, CIDV0_LEN, MAX_COLLECTION_HASH_LEN},
     nft_tier::TierName,
 };
 
@@ -10,7 +13,7 @@ pub type PaymentsVec<M> = ManagedVec<M, EsdtTokenPayment<M>>;
 pub type EgldValuePaymentsVecPair<M> = MultiValue2<BigUint<M>, PaymentsVec<M>>;
 pub type BrandId<M> = ManagedBuffer<M>;
 
-#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+#[derive(TypeAbi, TopEncode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
 pub struct BrandInfo<M: ManagedTypeApi> {
     pub collection_hash: CollectionHash<M>,
     pub token_display_name: ManagedBuffer<M>,
@@ -18,15 +21,84 @@ pub struct BrandInfo<M: ManagedTypeApi> {
     pub royalties: BigUint<M>,
     pub mint_period: TimePeriod,
     pub whitelist_expire_timestamp: u64,
+    /// false while `collection_hash` is a placeholder, until an admin reveals the brand
+    pub is_revealed: bool,
+}
+
+/// Brands created before variable-length collection hashes were supported
+/// start with the raw 46 bytes of a CIDv0 instead of a length-prefixed hash,
+/// and were always revealed.
+impl<M: ManagedTypeApi> TopDecode for BrandInfo<M> {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: TopDecodeInput,
+        H: DecodeErrorHandler,
+    {
+        let buffer = ManagedBuffer::<M>::top_decode_or_handle_err(input, h)?;
+        if !is_legacy_brand_info_layout(&buffer) {
+            let mut nested_input = ManagedBufferNestedDecodeInput::new(buffer);
+            let brand_info = Self::dep_decode_or_handle_err(&mut nested_input, h)?;
+            if !nested_input.is_depleted() {
+                return Result::Err(h.handle_error(DecodeError::INPUT_TOO_LONG));
+            }
+
+            return Result::Ok(brand_info);
+        }
+
+        let opt_collection_hash = buffer.copy_slice(0, CIDV0_LEN);
+        let opt_remaining_fields = buffer.copy_slice(CIDV0_LEN, buffer.len() - CIDV0_LEN);
+        let (collection_hash, remaining_fields) = match (opt_collection_hash, opt_remaining_fields)
+        {
+            (Some(collection_hash), Some(remaining_fields)) => (collection_hash, remaining_fields),
+            _ => return Result::Err(h.handle_error(DecodeError::INPUT_TOO_SHORT)),
+        };
+
+        let mut nested_input = ManagedBufferNestedDecodeInput::new(remaining_fields);
+        let brand_info = BrandInfo {
+            collection_hash,
+            token_display_name: ManagedBuffer::dep_decode_or_handle_err(&mut nested_input, h)?,
+            media_type: MediaType::dep_decode_or_handle_err(&mut nested_input, h)?,
+            royalties: BigUint::dep_decode_or_handle_err(&mut nested_input, h)?,
+            mint_period: TimePeriod::dep_decode_or_handle_err(&mut nested_input, h)?,
+            whitelist_expire_timestamp: u64::dep_decode_or_handle_err(&mut nested_input, h)?,
+            is_revealed: true,
+        };
+        if !nested_input.is_depleted() {
+            return Result::Err(h.handle_error(DecodeError::INPUT_TOO_LONG));
+        }
+
+        Result::Ok(brand_info)
+    }
 }
 
-#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+/// The new layout starts with the hash length, while the legacy one starts with `Qm`,
+/// which read as a length is far above the max hash length
+fn is_legacy_brand_info_layout<M: ManagedTypeApi>(buffer: &ManagedBuffer<M>) -> bool {
+    let mut hash_len_bytes = [0u8; 4];
+    if buffer.load_slice(0, &mut hash_len_bytes).is_err() {
+        return false;
+    }
+
+    u32::from_be_bytes(hash_len_bytes) as usize > MAX_COLLECTION_HASH_LEN
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
 pub struct TimePeriod {
     pub start: u64,
     pub end: u64,
 }
 
-#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+#[derive(
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    ManagedVecItem,
+    Clone,
+    PartialEq,
+    Debug,
+)]
 pub struct MintPrice<M: ManagedTypeApi> {
     pub token_id: EgldOrEsdtTokenIdentifier<M>,
     pub amount: BigUint<M>,
@@ -46,6 +118,60 @@ pub trait CommonStorageModule {
     #[storage_mapper("registeredCollectionHashes")]
     fn registered_collection_hashes(&self) -> UnorderedSetMapper<CollectionHash<Self::Api>>;
 
+    /// Returns false if the hash was already registered
+    fn register_collection_hash(&self, collection_hash: &CollectionHash<Self::Api>) -> bool {
+        self.migrate_legacy_collection_hash_index(collection_hash);
+
+        self.registered_collection_hashes()
+            .insert(collection_hash.clone())
+    }
+
+    fn unregister_collection_hash(&self, collection_hash: &CollectionHash<Self::Api>) {
+        let mut mapper = self.registered_collection_hashes();
+        if mapper.is_empty() {
+            return;
+        }
+
+        // the last hash is moved in place of the removed one, so its index is updated too
+        let last_hash = mapper.get_by_index(mapper.len());
+        self.migrate_legacy_collection_hash_index(&last_hash);
+        self.migrate_legacy_collection_hash_index(collection_hash);
+
+        let _ = mapper.swap_remove(collection_hash);
+    }
+
+    /// Hashes registered before variable-length collection hashes were supported
+    /// are indexed by their raw 46 bytes instead of the length-prefixed hash.
+    /// Moves the index entry to the current key, so the set finds the hash.
+    fn migrate_legacy_collection_hash_index(&self, collection_hash: &CollectionHash<Self::Api>) {
+        let legacy_hash =
+            match ManagedByteArray::<Self::Api, CIDV0_LEN>::try_from(collection_hash.clone()) {
+                Result::Ok(legacy_hash) => legacy_hash,
+                Result::Err(_) => return,
+            };
+        let legacy_index_mapper = self.legacy_collection_hash_index(&legacy_hash);
+        if legacy_index_mapper.is_empty() {
+            return;
+        }
+
+        self.collection_hash_index(collection_hash)
+            .set(legacy_index_mapper.get());
+        legacy_index_mapper.clear();
+    }
+
+    /// Same key as the index entries of `registered_collection_hashes`
+    #[storage_mapper("registeredCollectionHashes.index")]
+    fn collection_hash_index(
+        &self,
+        collection_hash: &CollectionHash<Self::Api>,
+    ) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("registeredCollectionHashes.index")]
+    fn legacy_collection_hash_index(
+        &self,
+        collection_hash: &ManagedByteArray<Self::Api, CIDV0_LEN>,
+    ) -> SingleValueMapper<usize>;
+
     #[view(getRegisteredBrands)]
     #[storage_mapper("registeredBrands")]
     fn registered_brands(&self) -> UnorderedSetMapper<BrandId<Self::Api>>;
@@ -54,9 +180,28 @@ pub trait CommonStorageModule {
     #[storage_mapper("nftTokenId")]
     fn nft_token(&self, brand_id: &BrandId<Self::Api>) -> NonFungibleTokenMapper<Self::Api>;
 
+    #[view(getBrandIdForToken)]
+    #[storage_mapper("brandIdForToken")]
+    fn brand_id_for_token(
+        &self,
+        token_id: &TokenIdentifier,
+    ) -> SingleValueMapper<BrandId<Self::Api>>;
+
+    /// Set for brands registered with an existing token, until `confirmTokenRoles` is called
+    #[view(needsTokenRoleConfirmation)]
+    #[storage_mapper("needsTokenRoleConfirmation")]
+    fn needs_token_role_confirmation(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> SingleValueMapper<bool>;
+
     #[storage_mapper("brandInfo")]
     fn brand_info(&self, brand_id: &BrandId<Self::Api>) -> SingleValueMapper<BrandInfo<Self::Api>>;
 
+    #[view(isMintClosed)]
+    #[storage_mapper("mintClosed")]
+    fn mint_closed(&self, brand_id: &BrandId<Self::Api>) -> SingleValueMapper<bool>;
+
     #[view(getPriceForTier)]
     #[storage_mapper("priceForTier")]
     fn price_for_tier(
@@ -65,6 +210,15 @@ pub trait CommonStorageModule {
         tier: &TierName<Self::Api>,
     ) -> SingleValueMapper<MintPrice<Self::Api>>;
 
+    /// Fixed price per NFT for each token accepted besides the one in `price_for_tier`
+    #[view(getExtraPriceOptionsForTier)]
+    #[storage_mapper("extraPriceOptionsForTier")]
+    fn extra_price_options_for_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> MapMapper<EgldOrEsdtTokenIdentifier, BigUint>;
+
     #[view(getTagsForBrand)]
     #[storage_mapper("tagsForBrand")]
     fn tags_for_brand(
@@ -75,4 +229,66 @@ pub trait CommonStorageModule {
     #[view(getMintWhitelist)]
     #[storage_mapper("mintWhitelist")]
     fn mint_whitelist(&self, brand_id: &BrandId<Self::Api>) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// The tier whose whitelist applies to the address, empty for the brand-wide one,
+    /// or `None` if the address is not whitelisted
+    fn get_whitelist_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        opt_tier: Option<&TierName<Self::Api>>,
+        address: &ManagedAddress,
+    ) -> Option<TierName<Self::Api>> {
+        if let Some(tier) = opt_tier {
+            if self.tier_mint_whitelist(brand_id, tier).contains(address) {
+                return Some(tier.clone());
+            }
+        }
+
+        if self.mint_whitelist(brand_id).contains(address) {
+            Some(TierName::new())
+        } else {
+            None
+        }
+    }
+
+    /// Without a tier, the brand-wide whitelist
+    fn get_whitelist_mapper(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        opt_tier: OptionalValue<TierName<Self::Api>>,
+    ) -> (TierName<Self::Api>, UnorderedSetMapper<ManagedAddress>) {
+        match opt_tier {
+            OptionalValue::Some(tier) => {
+                let mapper = self.tier_mint_whitelist(brand_id, &tier);
+                (tier, mapper)
+            }
+            OptionalValue::None => (TierName::new(), self.mint_whitelist(brand_id)),
+        }
+    }
+
+    /// Addresses whitelisted only for the given tier
+    #[view(getTierMintWhitelist)]
+    #[storage_mapper("tierMintWhitelist")]
+    fn tier_mint_whitelist(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// Empty tier for the brand-wide whitelist. 0 for no limit.
+    #[storage_mapper("whitelistQuota")]
+    fn whitelist_quota(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("whitelistMinted")]
+    fn whitelist_minted(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
 }
@@ -1,7 +1,7 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
-use crate::{common_storage::BrandId, nft_tier::TierName};
+use crate::{common_storage::BrandId, nft_attributes_builder::CollectionHash, nft_tier::TierName};
 
 #[elrond_wasm::module]
 pub trait EventsModule {
@@ -12,15 +12,94 @@ pub trait EventsModule {
         #[indexed] nft_token_id: &TokenIdentifier,
     );
 
+    #[event("brandCreationFailed")]
+    fn brand_creation_failed_event(
+        &self,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] original_caller: &ManagedAddress,
+        err_msg: &ManagedBuffer,
+    );
+
+    /// The token was issued after its brand creation was cleared, so no brand uses it
+    #[event("orphanTokenIssued")]
+    fn orphan_token_issued_event(
+        &self,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] original_caller: &ManagedAddress,
+        #[indexed] token_id: &TokenIdentifier,
+    );
+
+    /// Empty brand ID for contract-wide settings, like the default URI templates
+    #[event("brandUpdated")]
+    fn brand_updated_event(
+        &self,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] updated_field: &ManagedBuffer,
+    );
+
+    /// Empty brand ID and tier for the whole contract, empty tier for a whole brand
+    #[event("paused")]
+    fn paused_event(
+        &self,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] tier: &TierName<Self::Api>,
+    );
+
+    #[event("unpaused")]
+    fn unpaused_event(
+        &self,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] tier: &TierName<Self::Api>,
+    );
+
+    #[event("brandRevealed")]
+    fn brand_revealed_event(
+        &self,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] collection_hash: &CollectionHash<Self::Api>,
+    );
+
+    /// The recipient is the buyer, unless the NFTs were bought as a gift
     #[event("nftBought")]
     fn nft_bought_event(
         &self,
         #[indexed] buyer_address: &ManagedAddress,
+        #[indexed] recipient_address: &ManagedAddress,
         #[indexed] brand_id: &BrandId<Self::Api>,
         #[indexed] tier: &TierName<Self::Api>,
         nr_nfts_bought: usize,
     );
 
+    #[event("nftsRedeemed")]
+    fn nfts_redeemed_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] tier: &TierName<Self::Api>,
+        #[indexed] source_token_id: &TokenIdentifier,
+        nr_nfts_minted: usize,
+    );
+
+    #[event("nftsFused")]
+    fn nfts_fused_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] input_tier: &TierName<Self::Api>,
+        #[indexed] output_tier: &TierName<Self::Api>,
+        nr_input_nfts: usize,
+    );
+
+    /// Emitted for every `closeMint` batch.
+    /// Zero treasury address when the unsold NFTs were retired instead of minted.
+    #[event("mintClosed")]
+    fn mint_closed_event(
+        &self,
+        #[indexed] brand_id: &BrandId<Self::Api>,
+        #[indexed] treasury: &ManagedAddress,
+        nfts_closed: usize,
+    );
+
     #[event("nftGiveaway")]
     fn nft_giveaway_event(
         &self,
@@ -6,20 +6,40 @@ pub mod admin_whitelist;
 pub mod brand_creation;
 pub mod common_storage;
 pub mod events;
+pub mod merkle_whitelist;
+pub mod mint_phases;
+pub mod mint_vouchers;
+pub mod mystery_tier;
 pub mod nft_attributes_builder;
+pub mod nft_fusion;
 pub mod nft_marketplace_interactor;
 pub mod nft_minting;
+pub mod nft_redemption;
+pub mod nft_reveal;
 pub mod nft_tier;
+pub mod pause;
+pub mod pricing;
 pub mod royalties;
 pub mod views;
+pub mod wallet_limits;
 
 #[elrond_wasm::contract]
 pub trait NftMinter:
     common_storage::CommonStorageModule
     + admin_whitelist::AdminWhitelistModule
     + brand_creation::BrandCreationModule
+    + mint_phases::MintPhasesModule
+    + merkle_whitelist::MerkleWhitelistModule
+    + mint_vouchers::MintVouchersModule
+    + mystery_tier::MysteryTierModule
     + nft_minting::NftMintingModule
+    + nft_fusion::NftFusionModule
+    + nft_redemption::NftRedemptionModule
+    + nft_reveal::NftRevealModule
     + nft_tier::NftTierModule
+    + pause::PauseModule
+    + pricing::PricingModule
+    + wallet_limits::WalletLimitsModule
     + nft_attributes_builder::NftAttributesBuilderModule
     + royalties::RoyaltiesModule
     + nft_marketplace_interactor::NftMarketplaceInteractorModule
@@ -0,0 +1,113 @@
+elrond_wasm::imports!();
+
+use crate::{brand_creation::INVALID_BRAND_ID_ERR_MSG, common_storage::BrandId};
+
+pub const MERKLE_HASH_LEN: usize = 32;
+pub const MAX_MERKLE_PROOF_LEN: usize = 32;
+
+pub type MerkleHash<M> = ManagedByteArray<M, MERKLE_HASH_LEN>;
+pub type MerkleProof<M> = ManagedVec<M, MerkleHash<M>>;
+
+/// Allowance encoded in the leaf (0 for no limit), proof hashes from the leaf up to the root
+pub type MerkleProofArg<M> = MultiValue2<usize, MerkleProof<M>>;
+
+#[elrond_wasm::module]
+pub trait MerkleWhitelistModule:
+    crate::admin_whitelist::AdminWhitelistModule + crate::common_storage::CommonStorageModule
+{
+    /// Once a root is set, the brand's whitelist is checked against it
+    /// instead of the `mint_whitelist` address list.
+    #[endpoint(setWhitelistMerkleRoot)]
+    fn set_whitelist_merkle_root(&self, brand_id: BrandId<Self::Api>, root: MerkleHash<Self::Api>) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.whitelist_merkle_root(&brand_id).set(&root);
+    }
+
+    #[endpoint(clearWhitelistMerkleRoot)]
+    fn clear_whitelist_merkle_root(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+
+        self.whitelist_merkle_root(&brand_id).clear();
+    }
+
+    fn require_valid_merkle_proof(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        address: &ManagedAddress,
+        opt_proof: OptionalValue<MerkleProofArg<Self::Api>>,
+        nfts_to_mint: usize,
+    ) {
+        let (allowance, proof) = match opt_proof {
+            OptionalValue::Some(proof_arg) => proof_arg.into_tuple(),
+            OptionalValue::None => sc_panic!("Whitelist proof required"),
+        };
+        require!(proof.len() <= MAX_MERKLE_PROOF_LEN, "Proof too long");
+
+        let mut computed_hash = self.compute_merkle_leaf(address, allowance);
+        for sibling in &proof {
+            computed_hash = self.hash_merkle_pair(&computed_hash, &sibling);
+        }
+
+        let root = self.whitelist_merkle_root(brand_id).get();
+        require!(computed_hash == root, "Invalid whitelist proof");
+
+        let minted_mapper = self.merkle_whitelist_minted(brand_id, address);
+        let total_minted = minted_mapper.get() + nfts_to_mint;
+        require!(
+            allowance == 0 || total_minted <= allowance,
+            "Whitelist allowance exceeded"
+        );
+
+        minted_mapper.set(total_minted);
+    }
+
+    fn compute_merkle_leaf(
+        &self,
+        address: &ManagedAddress,
+        allowance: usize,
+    ) -> MerkleHash<Self::Api> {
+        let mut leaf_data = address.as_managed_buffer().clone();
+        leaf_data.append_bytes(&(allowance as u64).to_be_bytes()[..]);
+
+        self.crypto().keccak256(&leaf_data)
+    }
+
+    fn hash_merkle_pair(
+        &self,
+        first: &MerkleHash<Self::Api>,
+        second: &MerkleHash<Self::Api>,
+    ) -> MerkleHash<Self::Api> {
+        let first_bytes = first.to_byte_array();
+        let second_bytes = second.to_byte_array();
+        let (left, right) = if first_bytes <= second_bytes {
+            (first_bytes, second_bytes)
+        } else {
+            (second_bytes, first_bytes)
+        };
+
+        let mut pair_data = ManagedBuffer::new_from_bytes(&left[..]);
+        pair_data.append_bytes(&right[..]);
+
+        self.crypto().keccak256(&pair_data)
+    }
+
+    #[view(getWhitelistMerkleRoot)]
+    #[storage_mapper("whitelistMerkleRoot")]
+    fn whitelist_merkle_root(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> SingleValueMapper<MerkleHash<Self::Api>>;
+
+    #[view(getMerkleWhitelistMinted)]
+    #[storage_mapper("merkleWhitelistMinted")]
+    fn merkle_whitelist_minted(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
+}
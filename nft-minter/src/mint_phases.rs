@@ -0,0 +1,364 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    brand_creation::{
+        EXTRA_PRICE_OPTIONS_CONFLICT_ERR_MSG, INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG,
+    },
+    common_storage::{BrandId, BrandInfo, MintPrice, TimePeriod},
+    nft_tier::TierName,
+};
+
+pub type PhaseName<M> = ManagedBuffer<M>;
+
+pub const MAX_PHASES_PER_BRAND: usize = 10;
+const MAX_PHASE_NAME_LEN: usize = 32;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct MintPhase<M: ManagedTypeApi> {
+    pub name: PhaseName<M>,
+    pub period: TimePeriod,
+    pub whitelist_only: bool,
+    pub max_nfts_per_wallet: usize,
+}
+
+/// With `single_use_nonces`, each token nonce may only be used once for the brand
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Debug)]
+pub struct TokenGate<M: ManagedTypeApi> {
+    pub token_id: TokenIdentifier<M>,
+    pub single_use_nonces: bool,
+}
+
+/// Tier name, price for the phase
+pub type PhasePriceArgPair<M> = MultiValue2<TierName<M>, BigUint<M>>;
+
+#[elrond_wasm::module]
+pub trait MintPhasesModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+    + crate::pause::PauseModule
+    + crate::events::EventsModule
+{
+    /// Phases must be added in chronological order and may not overlap.
+    /// A `max_nfts_per_wallet` of 0 means there is no per-wallet limit for the phase.
+    /// Tiers that are not given a price keep their base price during the phase.
+    /// Tiers with extra price options may not be given a phase price.
+    /// Phase names may not be reused, as the NFTs minted per wallet are counted by name.
+    #[endpoint(addMintPhase)]
+    fn add_mint_phase(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        phase_name: PhaseName<Self::Api>,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        whitelist_only: bool,
+        max_nfts_per_wallet: usize,
+        tier_prices: MultiValueEncoded<PhasePriceArgPair<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        let name_len = phase_name.len();
+        require!(
+            name_len > 0 && name_len <= MAX_PHASE_NAME_LEN,
+            "Invalid phase name"
+        );
+        require!(start_timestamp < end_timestamp, "Invalid timestamps");
+
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        self.require_phase_change_allowed(&brand_id, &brand_info);
+        require!(
+            start_timestamp >= brand_info.mint_period.start
+                && end_timestamp <= brand_info.mint_period.end,
+            "Phase must be within the mint period"
+        );
+
+        let mut phases_mapper = self.mint_phases(&brand_id);
+        let nr_phases = phases_mapper.len();
+        require!(
+            nr_phases < MAX_PHASES_PER_BRAND,
+            "Max phases per brand limit exceeded"
+        );
+
+        for phase in phases_mapper.iter() {
+            require!(phase.name != phase_name, "Duplicate phase name");
+        }
+
+        let name_used_mapper = self.phase_name_used(&brand_id, &phase_name);
+        require!(!name_used_mapper.get(), "Phase name already used");
+        name_used_mapper.set(true);
+        if nr_phases > 0 {
+            let last_phase = phases_mapper.get(nr_phases);
+            require!(
+                start_timestamp >= last_phase.period.end,
+                "Phases must be in order and may not overlap"
+            );
+        }
+
+        let tiers_mapper = self.nft_tiers_for_brand(&brand_id);
+        for pair in tier_prices {
+            let (tier, amount) = pair.into_tuple();
+            require!(tiers_mapper.contains(&tier), INVALID_TIER_ERR_MSG);
+            require!(
+                self.extra_price_options_for_tier(&brand_id, &tier)
+                    .is_empty(),
+                EXTRA_PRICE_OPTIONS_CONFLICT_ERR_MSG
+            );
+
+            let base_price = self.price_for_tier(&brand_id, &tier).get();
+            self.phase_price_for_tier(&brand_id, &phase_name, &tier)
+                .set(&MintPrice {
+                    token_id: base_price.token_id,
+                    amount,
+                });
+        }
+
+        let _ = phases_mapper.push(&MintPhase {
+            name: phase_name,
+            period: TimePeriod {
+                start: start_timestamp,
+                end: end_timestamp,
+            },
+            whitelist_only,
+            max_nfts_per_wallet,
+        });
+    }
+
+    #[endpoint(clearMintPhases)]
+    fn clear_mint_phases(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        self.require_phase_change_allowed(&brand_id, &brand_info);
+
+        let mut phases_mapper = self.mint_phases(&brand_id);
+        for phase in phases_mapper.iter() {
+            self.phase_token_gate(&brand_id, &phase.name).clear();
+            for tier in self.nft_tiers_for_brand(&brand_id).iter() {
+                self.phase_price_for_tier(&brand_id, &phase.name, &tier)
+                    .clear();
+            }
+        }
+
+        phases_mapper.clear();
+    }
+
+    /// Only holders of an NFT or SFT from `token_id` may buy during the phase.
+    /// They send one along with the price, as the first payment of a multi-ESDT transfer,
+    /// and get it back in the same transaction.
+    /// As EGLD may not be part of a multi-ESDT transfer, gated tiers must be priced in an ESDT.
+    #[endpoint(setPhaseTokenGate)]
+    fn set_phase_token_gate(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        phase_name: PhaseName<Self::Api>,
+        token_id: TokenIdentifier,
+        single_use_nonces: bool,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.mint_phases(&brand_id)
+                .iter()
+                .any(|phase| phase.name == phase_name),
+            "Invalid phase name"
+        );
+        require!(token_id.is_valid_esdt_identifier(), "Invalid token ID");
+
+        self.phase_token_gate(&brand_id, &phase_name)
+            .set(&TokenGate {
+                token_id,
+                single_use_nonces,
+            });
+    }
+
+    #[endpoint(clearPhaseTokenGate)]
+    fn clear_phase_token_gate(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        phase_name: PhaseName<Self::Api>,
+    ) {
+        self.require_caller_is_admin();
+
+        self.phase_token_gate(&brand_id, &phase_name).clear();
+    }
+
+    fn require_phase_change_allowed(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        brand_info: &BrandInfo<Self::Api>,
+    ) {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let is_mint_active = current_timestamp >= brand_info.mint_period.start
+            && current_timestamp < brand_info.mint_period.end;
+        require!(
+            !is_mint_active || self.is_brand_paused(brand_id),
+            "May not change phases during mint unless paused"
+        );
+    }
+
+    /// Splits the call's payments into the mint payment and, during a gated phase, the gate token
+    fn get_gated_payments(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+    ) -> (
+        EgldOrEsdtTokenPayment<Self::Api>,
+        Option<EsdtTokenPayment<Self::Api>>,
+    ) {
+        let gate_mapper = match opt_phase {
+            Some(phase) => self.phase_token_gate(brand_id, &phase.name),
+            None => return (self.call_value().egld_or_single_esdt(), None),
+        };
+        if gate_mapper.is_empty() {
+            return (self.call_value().egld_or_single_esdt(), None);
+        }
+
+        let gate = gate_mapper.get();
+        let payments = self.call_value().all_esdt_transfers();
+        require!(
+            payments.len() == 2,
+            "Must send a gate token along with the payment"
+        );
+
+        let gate_payment = payments.get(0);
+        require!(
+            gate_payment.token_identifier == gate.token_id && gate_payment.token_nonce > 0,
+            "Invalid gate token"
+        );
+
+        if gate.single_use_nonces {
+            let used_mapper =
+                self.gate_nonce_used(brand_id, &gate.token_id, gate_payment.token_nonce);
+            require!(!used_mapper.get(), "Gate token already used");
+
+            used_mapper.set(true);
+        }
+
+        (payments.get(1).into(), Some(gate_payment))
+    }
+
+    fn require_not_token_gated(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+    ) {
+        if let Some(phase) = opt_phase {
+            require!(
+                self.phase_token_gate(brand_id, &phase.name).is_empty(),
+                "Phase requires a gate token"
+            );
+        }
+    }
+
+    fn get_active_mint_phase(&self, brand_id: &BrandId<Self::Api>) -> Option<MintPhase<Self::Api>> {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        self.mint_phases(brand_id).iter().find(|phase| {
+            current_timestamp >= phase.period.start && current_timestamp < phase.period.end
+        })
+    }
+
+    fn get_mint_price_for_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+    ) -> MintPrice<Self::Api> {
+        if let Some(phase) = opt_phase {
+            let phase_price_mapper = self.phase_price_for_tier(brand_id, &phase.name, tier);
+            if !phase_price_mapper.is_empty() {
+                return phase_price_mapper.get();
+            }
+        }
+
+        self.price_for_tier(brand_id, tier).get()
+    }
+
+    fn has_any_phase_price(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> bool {
+        self.mint_phases(brand_id).iter().any(|phase| {
+            !self
+                .phase_price_for_tier(brand_id, &phase.name, tier)
+                .is_empty()
+        })
+    }
+
+    fn add_minted_in_phase(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        phase: &MintPhase<Self::Api>,
+        address: &ManagedAddress,
+        nfts_to_mint: usize,
+    ) {
+        let minted_mapper = self.minted_in_phase(brand_id, &phase.name, address);
+        let total_minted = minted_mapper.get() + nfts_to_mint;
+        if phase.max_nfts_per_wallet > 0 {
+            require!(
+                total_minted <= phase.max_nfts_per_wallet,
+                "Phase wallet limit exceeded"
+            );
+        }
+
+        minted_mapper.set(total_minted);
+    }
+
+    #[view(getMintPhases)]
+    #[storage_mapper("mintPhases")]
+    fn mint_phases(&self, brand_id: &BrandId<Self::Api>) -> VecMapper<MintPhase<Self::Api>>;
+
+    #[view(getPhasePriceForTier)]
+    #[storage_mapper("phasePriceForTier")]
+    fn phase_price_for_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        phase_name: &PhaseName<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> SingleValueMapper<MintPrice<Self::Api>>;
+
+    #[view(getPhaseTokenGate)]
+    #[storage_mapper("phaseTokenGate")]
+    fn phase_token_gate(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        phase_name: &PhaseName<Self::Api>,
+    ) -> SingleValueMapper<TokenGate<Self::Api>>;
+
+    #[view(isGateNonceUsed)]
+    #[storage_mapper("gateNonceUsed")]
+    fn gate_nonce_used(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        token_id: &TokenIdentifier,
+        nonce: u64,
+    ) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("phaseNameUsed")]
+    fn phase_name_used(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        phase_name: &PhaseName<Self::Api>,
+    ) -> SingleValueMapper<bool>;
+
+    #[view(getMintedInPhase)]
+    #[storage_mapper("mintedInPhase")]
+    fn minted_in_phase(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        phase_name: &PhaseName<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
+}
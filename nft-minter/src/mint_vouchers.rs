@@ -0,0 +1,113 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use elrond_wasm::{
+    api::{ED25519_KEY_BYTE_LEN, ED25519_SIGNATURE_BYTE_LEN},
+    elrond_codec::TopEncode,
+};
+
+use crate::{
+    brand_creation::INVALID_BRAND_ID_ERR_MSG, common_storage::BrandId, nft_tier::TierName,
+};
+
+const MAX_VOUCHER_MESSAGE_LEN: usize = 256;
+
+pub type VoucherSignerKey<M> = ManagedByteArray<M, ED25519_KEY_BYTE_LEN>;
+pub type VoucherSignature<M> = ManagedByteArray<M, ED25519_SIGNATURE_BYTE_LEN>;
+
+/// The backend signs the top-encoded voucher, i.e. the concatenation of:
+/// minter SC address (32 bytes), caller address (32 bytes),
+/// brand ID and tier (each as 4 bytes big endian length + bytes),
+/// NFTs to buy (4 bytes big endian), nonce and expire timestamp (8 bytes big endian each).
+#[derive(TopEncode)]
+pub struct MintVoucher<M: ManagedTypeApi> {
+    pub minter_address: ManagedAddress<M>,
+    pub caller: ManagedAddress<M>,
+    pub brand_id: BrandId<M>,
+    pub tier: TierName<M>,
+    pub nfts_to_buy: usize,
+    pub nonce: u64,
+    pub expire_timestamp: u64,
+}
+
+#[elrond_wasm::module]
+pub trait MintVouchersModule:
+    crate::admin_whitelist::AdminWhitelistModule + crate::common_storage::CommonStorageModule
+{
+    /// Once a signer is set, the brand may only be bought with vouchers signed by it,
+    /// which replace the whitelist check.
+    #[endpoint(setMintVoucherSigner)]
+    fn set_mint_voucher_signer(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        signer: VoucherSignerKey<Self::Api>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.mint_voucher_signer(&brand_id).set(&signer);
+    }
+
+    #[endpoint(clearMintVoucherSigner)]
+    fn clear_mint_voucher_signer(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+
+        self.mint_voucher_signer(&brand_id).clear();
+    }
+
+    fn require_valid_mint_voucher(
+        &self,
+        voucher: &MintVoucher<Self::Api>,
+        signature: &VoucherSignature<Self::Api>,
+    ) {
+        let signer_mapper = self.mint_voucher_signer(&voucher.brand_id);
+        require!(
+            !signer_mapper.is_empty(),
+            "Brand does not use mint vouchers"
+        );
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            current_timestamp < voucher.expire_timestamp,
+            "Mint voucher expired"
+        );
+
+        let used_mapper = self.mint_voucher_used(&voucher.brand_id, voucher.nonce);
+        require!(!used_mapper.get(), "Mint voucher already used");
+
+        let mut message = ManagedBuffer::new();
+        if voucher.top_encode(&mut message).is_err() {
+            sc_panic!("Could not encode mint voucher");
+        }
+        require!(
+            message.len() <= MAX_VOUCHER_MESSAGE_LEN,
+            "Mint voucher too long"
+        );
+
+        let signer = signer_mapper.get();
+        let is_valid_signature = self
+            .crypto()
+            .verify_ed25519_legacy_managed::<MAX_VOUCHER_MESSAGE_LEN>(&signer, &message, signature);
+        require!(is_valid_signature, "Invalid mint voucher signature");
+
+        used_mapper.set(true);
+    }
+
+    #[view(getMintVoucherSigner)]
+    #[storage_mapper("mintVoucherSigner")]
+    fn mint_voucher_signer(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> SingleValueMapper<VoucherSignerKey<Self::Api>>;
+
+    #[view(isMintVoucherUsed)]
+    #[storage_mapper("mintVoucherUsed")]
+    fn mint_voucher_used(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        nonce: u64,
+    ) -> SingleValueMapper<bool>;
+}
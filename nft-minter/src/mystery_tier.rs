@@ -0,0 +1,125 @@
+elrond_wasm::imports!();
+
+use crate::{
+    brand_creation::{INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG},
+    common_storage::{BrandId, MintPrice},
+    nft_tier::{TierName, MAX_TIERS_PER_BRAND},
+};
+
+/// Tier name, relative odds of the tier being picked
+pub type MysteryTierOddsArg<M> = MultiValue2<TierName<M>, u32>;
+
+#[elrond_wasm::module]
+pub trait MysteryTierModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+    + crate::pause::PauseModule
+    + crate::wallet_limits::WalletLimitsModule
+    + crate::events::EventsModule
+{
+    /// Sells NFTs from a random tier for a single price.
+    /// Without odds, tiers are weighted by their remaining NFTs.
+    #[endpoint(setMysteryTier)]
+    fn set_mystery_tier(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        price_token_id: EgldOrEsdtTokenIdentifier,
+        price_amount: BigUint,
+        tier_odds: MultiValueEncoded<MysteryTierOddsArg<Self::Api>>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(price_token_id.is_valid(), "Invalid price token");
+
+        let tiers_mapper = self.nft_tiers_for_brand(&brand_id);
+        let mut odds_mapper = self.mystery_tier_odds(&brand_id);
+        odds_mapper.clear();
+        for pair in tier_odds {
+            let (tier, odds) = pair.into_tuple();
+            require!(tiers_mapper.contains(&tier), INVALID_TIER_ERR_MSG);
+
+            let _ = odds_mapper.insert(tier, odds);
+        }
+
+        self.mystery_tier_price(&brand_id).set(&MintPrice {
+            token_id: price_token_id,
+            amount: price_amount,
+        });
+    }
+
+    #[endpoint(clearMysteryTier)]
+    fn clear_mystery_tier(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+
+        self.mystery_tier_price(&brand_id).clear();
+        self.mystery_tier_odds(&brand_id).clear();
+    }
+
+    /// Only tiers with NFTs left that are not paused, and where the buyer
+    /// has not reached the tier's wallet limit, may be picked
+    fn pick_mystery_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        buyer: &ManagedAddress,
+    ) -> TierName<Self::Api> {
+        let odds_mapper = self.mystery_tier_odds(brand_id);
+        let use_odds = !odds_mapper.is_empty();
+
+        let mut weighted_tiers =
+            ArrayVec::<(TierName<Self::Api>, usize), MAX_TIERS_PER_BRAND>::new();
+        let mut total_weight = 0;
+        for tier in self.nft_tiers_for_brand(brand_id).iter() {
+            let available_nfts = self.get_available_nfts(brand_id, &tier);
+            if available_nfts == 0
+                || self.is_tier_paused(brand_id, &tier)
+                || self.is_tier_wallet_limit_reached(brand_id, &tier, buyer)
+            {
+                continue;
+            }
+
+            let weight = if use_odds {
+                odds_mapper.get(&tier).unwrap_or_default() as usize
+            } else {
+                available_nfts
+            };
+            if weight == 0 {
+                continue;
+            }
+
+            total_weight += weight;
+            unsafe {
+                weighted_tiers.push_unchecked((tier, weight));
+            }
+        }
+        require!(total_weight > 0, "No NFTs available for mystery tier");
+
+        let mut rand_weight = self.get_random_usize(0, total_weight);
+        for (tier, weight) in weighted_tiers {
+            if rand_weight < weight {
+                return tier;
+            }
+
+            rand_weight -= weight;
+        }
+
+        sc_panic!("No NFTs available for mystery tier");
+    }
+
+    #[view(getMysteryTierPrice)]
+    #[storage_mapper("mysteryTierPrice")]
+    fn mystery_tier_price(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> SingleValueMapper<MintPrice<Self::Api>>;
+
+    #[view(getMysteryTierOdds)]
+    #[storage_mapper("mysteryTierOdds")]
+    fn mystery_tier_odds(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> MapMapper<TierName<Self::Api>, u32>;
+}
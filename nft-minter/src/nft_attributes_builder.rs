@@ -1,7 +1,9 @@
 elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
 
-use crate::common_storage::BrandId;
+use crate::common_storage::{BrandId, BrandInfo};
 
+static METADATA_PREFIX: &[u8] = b"metadata:";
 static TAGS_PREFIX: &[u8] = b"tags:";
 static TAG_SEPARATOR: &[u8] = b",";
 static ATTRIBUTES_SEPARATOR: &[u8] = b";";
@@ -23,42 +25,97 @@ static SUPPORTED_MEDIA_TYPES: &[&[u8]] = &[
     b"webm",
 ];
 const MAX_MEDIA_TYPE_LEN: usize = 9;
-pub const COLLECTION_HASH_LEN: usize = 46;
+
+static HASH_PLACEHOLDER: &[u8] = b"{hash}";
+static ID_PLACEHOLDER: &[u8] = b"{id}";
+static EXTENSION_PLACEHOLDER: &[u8] = b"{ext}";
+static JSON_EXTENSION: &[u8] = b"json";
+static COLLECTION_FILE_NAME: &[u8] = b"collection";
+static DEFAULT_FILE_URI_TEMPLATE: &[u8] = b"https://ipfs.io/ipfs/{hash}/{id}.{ext}";
+static DEFAULT_METADATA_TEMPLATE: &[u8] = b"{hash}/{id}.json";
+pub const MAX_URI_TEMPLATE_LEN: usize = 256;
+
+static CIDV0_PREFIX: &[u8] = b"Qm";
+static CIDV1_BASE32_PREFIX: &[u8] = b"b";
+static BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+static BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+pub const CIDV0_LEN: usize = 46;
+const MIN_CIDV1_LEN: usize = 59;
+pub const MAX_COLLECTION_HASH_LEN: usize = 128;
 
 pub type Uri<M> = ManagedBuffer<M>;
-pub type CollectionHash<M> = ManagedByteArray<M, COLLECTION_HASH_LEN>;
+pub type UriTemplate<M> = ManagedBuffer<M>;
+/// Either a CIDv0 (`Qm...`) or a base32 CIDv1 (`b...`)
+pub type CollectionHash<M> = ManagedBuffer<M>;
 pub type Tag<M> = ManagedBuffer<M>;
 pub type MediaType<M> = ManagedBuffer<M>;
 pub type GenericAttributes<M> = ManagedBuffer<M>;
 
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct UriTemplates<M: ManagedTypeApi> {
+    /// Used for the media file, the NFT JSON and the collection JSON URIs
+    pub file_uri: UriTemplate<M>,
+    /// Used for the `metadata:` attribute, where `{ext}` is always `json`
+    pub metadata: UriTemplate<M>,
+}
+
 #[elrond_wasm::module]
 pub trait NftAttributesBuilderModule: crate::common_storage::CommonStorageModule {
     fn build_nft_attributes(
         &self,
+        uri_templates: &UriTemplates<Self::Api>,
         collection_hash: &CollectionHash<Self::Api>,
         brand_id: &BrandId<Self::Api>,
         nft_id: UniqueId,
     ) -> GenericAttributes<Self::Api> {
-        let mut attributes = self.build_attributes_metadata_part(collection_hash, nft_id);
+        let mut attributes =
+            self.build_attributes_metadata_part(uri_templates, collection_hash, nft_id);
+        self.append_attributes_tags_part(&mut attributes, brand_id);
+
+        attributes
+    }
+
+    /// Placeholder NFTs point to the collection's JSON, so their attributes don't reveal the NFT's ID
+    fn build_placeholder_nft_attributes(
+        &self,
+        uri_templates: &UriTemplates<Self::Api>,
+        collection_hash: &CollectionHash<Self::Api>,
+        brand_id: &BrandId<Self::Api>,
+    ) -> GenericAttributes<Self::Api> {
+        let mut attributes = GenericAttributes::new_from_bytes(METADATA_PREFIX);
+        attributes.append(&self.build_collection_json_file_uri(uri_templates, collection_hash));
+        self.append_attributes_tags_part(&mut attributes, brand_id);
+
+        attributes
+    }
+
+    fn append_attributes_tags_part(
+        &self,
+        attributes: &mut GenericAttributes<Self::Api>,
+        brand_id: &BrandId<Self::Api>,
+    ) {
         let tags_attributes = self.build_attributes_tags_part(brand_id);
         if !tags_attributes.is_empty() {
             attributes.append_bytes(ATTRIBUTES_SEPARATOR);
             attributes.append(&tags_attributes);
         }
-
-        attributes
     }
 
     fn build_attributes_metadata_part(
         &self,
+        uri_templates: &UriTemplates<Self::Api>,
         collection_hash: &CollectionHash<Self::Api>,
         nft_id: UniqueId,
     ) -> GenericAttributes<Self::Api> {
-        sc_format!(
-            "metadata:{}/{}.json",
-            collection_hash.as_managed_buffer(),
-            nft_id
-        )
+        let mut attributes = GenericAttributes::new_from_bytes(METADATA_PREFIX);
+        attributes.append(&self.render_uri_template(
+            &uri_templates.metadata,
+            collection_hash,
+            &sc_format!("{}", nft_id),
+            &ManagedBuffer::new_from_bytes(JSON_EXTENSION),
+        ));
+
+        attributes
     }
 
     fn build_attributes_tags_part(
@@ -84,42 +141,184 @@ pub trait NftAttributesBuilderModule: crate::common_storage::CommonStorageModule
         tags_attributes
     }
 
+    fn build_nft_uris(
+        &self,
+        uri_templates: &UriTemplates<Self::Api>,
+        brand_info: &BrandInfo<Self::Api>,
+        nft_id: UniqueId,
+    ) -> ManagedVec<Uri<Self::Api>> {
+        let nft_uri = self.build_nft_main_file_uri(
+            uri_templates,
+            &brand_info.collection_hash,
+            nft_id,
+            &brand_info.media_type,
+        );
+        let nft_json =
+            self.build_nft_json_file_uri(uri_templates, &brand_info.collection_hash, nft_id);
+        let collection_json =
+            self.build_collection_json_file_uri(uri_templates, &brand_info.collection_hash);
+
+        let mut uris = ManagedVec::new();
+        uris.push(nft_uri);
+        uris.push(nft_json);
+        uris.push(collection_json);
+
+        uris
+    }
+
+    /// Placeholder NFTs only get the collection's URI, which doesn't depend on the NFT.
+    /// URIs can only be appended, so the real ones are added after it on reveal.
+    fn build_placeholder_nft_uris(
+        &self,
+        uri_templates: &UriTemplates<Self::Api>,
+        collection_hash: &CollectionHash<Self::Api>,
+    ) -> ManagedVec<Uri<Self::Api>> {
+        ManagedVec::from_single_item(
+            self.build_collection_json_file_uri(uri_templates, collection_hash),
+        )
+    }
+
     fn build_nft_main_file_uri(
         &self,
+        uri_templates: &UriTemplates<Self::Api>,
         collection_hash: &CollectionHash<Self::Api>,
         nft_id: UniqueId,
         media_type: &MediaType<Self::Api>,
     ) -> Uri<Self::Api> {
-        sc_format!(
-            "https://ipfs.io/ipfs/{}/{}.{}",
-            collection_hash.as_managed_buffer(),
-            nft_id,
-            media_type
+        self.render_uri_template(
+            &uri_templates.file_uri,
+            collection_hash,
+            &sc_format!("{}", nft_id),
+            media_type,
         )
     }
 
     fn build_nft_json_file_uri(
         &self,
+        uri_templates: &UriTemplates<Self::Api>,
         collection_hash: &CollectionHash<Self::Api>,
         nft_id: UniqueId,
     ) -> Uri<Self::Api> {
-        sc_format!(
-            "https://ipfs.io/ipfs/{}/{}.json",
-            collection_hash.as_managed_buffer(),
-            nft_id,
+        self.render_uri_template(
+            &uri_templates.file_uri,
+            collection_hash,
+            &sc_format!("{}", nft_id),
+            &ManagedBuffer::new_from_bytes(JSON_EXTENSION),
         )
     }
 
     fn build_collection_json_file_uri(
         &self,
+        uri_templates: &UriTemplates<Self::Api>,
         collection_hash: &CollectionHash<Self::Api>,
     ) -> Uri<Self::Api> {
-        sc_format!(
-            "https://ipfs.io/ipfs/{}/collection.json",
-            collection_hash.as_managed_buffer(),
+        self.render_uri_template(
+            &uri_templates.file_uri,
+            collection_hash,
+            &ManagedBuffer::new_from_bytes(COLLECTION_FILE_NAME),
+            &ManagedBuffer::new_from_bytes(JSON_EXTENSION),
         )
     }
 
+    /// The brand's templates, or the contract-wide ones if the brand has none set
+    fn get_uri_templates(&self, brand_id: &BrandId<Self::Api>) -> UriTemplates<Self::Api> {
+        let brand_templates_mapper = self.uri_templates_for_brand(brand_id);
+        if !brand_templates_mapper.is_empty() {
+            return brand_templates_mapper.get();
+        }
+
+        let default_templates_mapper = self.default_uri_templates();
+        if !default_templates_mapper.is_empty() {
+            return default_templates_mapper.get();
+        }
+
+        UriTemplates {
+            file_uri: UriTemplate::new_from_bytes(DEFAULT_FILE_URI_TEMPLATE),
+            metadata: UriTemplate::new_from_bytes(DEFAULT_METADATA_TEMPLATE),
+        }
+    }
+
+    fn render_uri_template(
+        &self,
+        template: &UriTemplate<Self::Api>,
+        hash: &ManagedBuffer,
+        id: &ManagedBuffer,
+        extension: &ManagedBuffer,
+    ) -> Uri<Self::Api> {
+        let template_len = template.len();
+        let mut template_static_buffer = [0u8; MAX_URI_TEMPLATE_LEN];
+        let template_bytes = &mut template_static_buffer[..template_len];
+        let _ = template.load_slice(0, template_bytes);
+
+        let mut uri = Uri::new();
+        let mut chunk_start = 0;
+        let mut i = 0;
+        while i < template_len {
+            let remaining_bytes = &template_bytes[i..];
+            let opt_replacement = if remaining_bytes.starts_with(HASH_PLACEHOLDER) {
+                Some((hash, HASH_PLACEHOLDER.len()))
+            } else if remaining_bytes.starts_with(ID_PLACEHOLDER) {
+                Some((id, ID_PLACEHOLDER.len()))
+            } else if remaining_bytes.starts_with(EXTENSION_PLACEHOLDER) {
+                Some((extension, EXTENSION_PLACEHOLDER.len()))
+            } else {
+                None
+            };
+
+            match opt_replacement {
+                Some((value, placeholder_len)) => {
+                    uri.append_bytes(&template_bytes[chunk_start..i]);
+                    uri.append(value);
+                    i += placeholder_len;
+                    chunk_start = i;
+                }
+                None => i += 1,
+            }
+        }
+        uri.append_bytes(&template_bytes[chunk_start..]);
+
+        uri
+    }
+
+    /// Templates must contain the `{id}` placeholder, otherwise all NFTs would share the same URI
+    fn is_valid_uri_template(&self, template: &UriTemplate<Self::Api>) -> bool {
+        let template_len = template.len();
+        if template_len == 0 || template_len > MAX_URI_TEMPLATE_LEN {
+            return false;
+        }
+
+        let mut template_static_buffer = [0u8; MAX_URI_TEMPLATE_LEN];
+        let template_bytes = &mut template_static_buffer[..template_len];
+        let _ = template.load_slice(0, template_bytes);
+
+        template_bytes
+            .windows(ID_PLACEHOLDER.len())
+            .any(|window| window == ID_PLACEHOLDER)
+    }
+
+    fn is_valid_collection_hash(&self, collection_hash: &CollectionHash<Self::Api>) -> bool {
+        let hash_len = collection_hash.len();
+        if hash_len > MAX_COLLECTION_HASH_LEN {
+            return false;
+        }
+
+        let mut hash_static_buffer = [0u8; MAX_COLLECTION_HASH_LEN];
+        let hash_bytes = &mut hash_static_buffer[..hash_len];
+        let _ = collection_hash.load_slice(0, hash_bytes);
+
+        if hash_bytes.starts_with(CIDV0_PREFIX) {
+            return hash_len == CIDV0_LEN && hash_bytes.iter().all(|c| BASE58_ALPHABET.contains(c));
+        }
+        if hash_bytes.starts_with(CIDV1_BASE32_PREFIX) {
+            return hash_len >= MIN_CIDV1_LEN
+                && hash_bytes[CIDV1_BASE32_PREFIX.len()..]
+                    .iter()
+                    .all(|c| BASE32_ALPHABET.contains(c));
+        }
+
+        false
+    }
+
     fn is_supported_media_type(&self, media_type: &MediaType<Self::Api>) -> bool {
         let media_type_len = media_type.len();
         if media_type_len > MAX_MEDIA_TYPE_LEN {
@@ -134,4 +333,15 @@ pub trait NftAttributesBuilderModule: crate::common_storage::CommonStorageModule
         #[allow(clippy::redundant_slicing)]
         SUPPORTED_MEDIA_TYPES.contains(&&slice[..])
     }
+
+    #[view(getDefaultUriTemplates)]
+    #[storage_mapper("defaultUriTemplates")]
+    fn default_uri_templates(&self) -> SingleValueMapper<UriTemplates<Self::Api>>;
+
+    #[view(getUriTemplatesForBrand)]
+    #[storage_mapper("uriTemplatesForBrand")]
+    fn uri_templates_for_brand(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> SingleValueMapper<UriTemplates<Self::Api>>;
 }
@@ -0,0 +1,146 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    brand_creation::{INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG},
+    common_storage::BrandId,
+    nft_tier::TierName,
+};
+
+pub const FUSION_OUTPUT_NFTS: usize = 1;
+
+/// `input_nfts` NFTs of the recipe's tier are exchanged for one random NFT of `output_tier`.
+/// The inputs are burned if `burn_inputs` is set, otherwise they are locked in the SC.
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Debug)]
+pub struct FusionRecipe<M: ManagedTypeApi> {
+    pub input_nfts: usize,
+    pub output_tier: TierName<M>,
+    pub burn_inputs: bool,
+}
+
+#[elrond_wasm::module]
+pub trait NftFusionModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+    + crate::nft_reveal::NftRevealModule
+    + crate::nft_attributes_builder::NftAttributesBuilderModule
+    + crate::events::EventsModule
+{
+    /// Replaces the recipe for `input_tier`, see `fuseNfts`.
+    /// Burning requires the SC to have the NFT burn role for the brand's token,
+    /// which is checked here rather than on every fusion.
+    #[endpoint(setFusionRecipe)]
+    fn set_fusion_recipe(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        input_tier: TierName<Self::Api>,
+        input_nfts: usize,
+        output_tier: TierName<Self::Api>,
+        burn_inputs: bool,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        let tiers_mapper = self.nft_tiers_for_brand(&brand_id);
+        require!(
+            tiers_mapper.contains(&input_tier) && tiers_mapper.contains(&output_tier),
+            INVALID_TIER_ERR_MSG
+        );
+        require!(
+            input_tier != output_tier,
+            "Fusion must output a different tier"
+        );
+        require!(input_nfts > 0, "Invalid number of input NFTs");
+
+        if burn_inputs {
+            let nft_token_id = self.nft_token(&brand_id).get_token_id();
+            let roles = self.blockchain().get_esdt_local_roles(&nft_token_id);
+            require!(
+                roles.has_role(&EsdtLocalRole::NftBurn),
+                "NFT burn role not set"
+            );
+        }
+
+        let _ = self.fusion_recipes(&brand_id).insert(
+            input_tier,
+            FusionRecipe {
+                input_nfts,
+                output_tier,
+                burn_inputs,
+            },
+        );
+    }
+
+    #[endpoint(removeFusionRecipe)]
+    fn remove_fusion_recipe(&self, brand_id: BrandId<Self::Api>, input_tier: TierName<Self::Api>) {
+        self.require_caller_is_admin();
+
+        let _ = self.fusion_recipes(&brand_id).remove(&input_tier);
+    }
+
+    /// All NFTs sent must be of the same tier, and exactly as many as its recipe requires.
+    /// Only NFTs whose tier was recorded at mint can be fused.
+    /// Burns or locks them, and returns their tier along with the recipe.
+    fn take_fusion_inputs(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> (TierName<Self::Api>, FusionRecipe<Self::Api>) {
+        let payments = self.call_value().all_esdt_transfers();
+        let nft_token_id = self.nft_token(brand_id).get_token_id();
+        let mut opt_input_tier: Option<TierName<Self::Api>> = None;
+        for payment in &payments {
+            require!(payment.token_identifier == nft_token_id, "Invalid payment");
+
+            let tier_mapper = self.tier_for_nonce(brand_id, payment.token_nonce);
+            require!(!tier_mapper.is_empty(), "Unknown tier for NFT");
+
+            let tier = tier_mapper.get();
+            match &opt_input_tier {
+                Some(input_tier) => {
+                    require!(*input_tier == tier, "All NFTs must be of the same tier")
+                }
+                None => opt_input_tier = Some(tier),
+            }
+        }
+
+        let input_tier = match opt_input_tier {
+            Some(tier) => tier,
+            None => sc_panic!("Invalid number of input NFTs"),
+        };
+        let recipe = match self.fusion_recipes(brand_id).get(&input_tier) {
+            Some(recipe) => recipe,
+            None => sc_panic!("No fusion recipe for tier"),
+        };
+        require!(
+            payments.len() == recipe.input_nfts,
+            "Invalid number of input NFTs"
+        );
+
+        if recipe.burn_inputs {
+            for payment in &payments {
+                self.send().esdt_local_burn(
+                    &payment.token_identifier,
+                    payment.token_nonce,
+                    &payment.amount,
+                );
+                self.tier_for_nonce(brand_id, payment.token_nonce).clear();
+                self.unrevealed_nft_id(brand_id, payment.token_nonce)
+                    .clear();
+            }
+        }
+
+        (input_tier, recipe)
+    }
+
+    /// Input tier, recipe
+    #[view(getFusionRecipes)]
+    #[storage_mapper("fusionRecipes")]
+    fn fusion_recipes(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> MapMapper<TierName<Self::Api>, FusionRecipe<Self::Api>>;
+}
@@ -2,16 +2,41 @@ elrond_wasm::imports!();
 
 use crate::{
     brand_creation::{INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG},
-    common_storage::{BrandId, BrandInfo, PaymentsVec},
-    nft_tier::TierName,
+    common_storage::{BrandId, BrandInfo, MintPrice, PaymentsVec},
+    merkle_whitelist::MerkleProofArg,
+    mint_phases::MintPhase,
+    mint_vouchers::{MintVoucher, VoucherSignature},
+    nft_fusion::FUSION_OUTPUT_NFTS,
+    nft_tier::{TierName, MAX_TIERS_PER_BRAND},
+    wallet_limits::GiftLimitsParty,
 };
 
 const NFT_AMOUNT: u32 = 1;
+const CLOSE_MINT_GAS_RESERVE: u64 = 5_000_000;
+const TREASURY_MINT_GAS_PER_NFT: u64 = 10_000_000;
+const RETIRE_GAS_PER_NFT: u64 = 1_000_000;
+
+/// How the buyer proves they may mint while a whitelist is required
+pub enum MintAuthorization<M: ManagedTypeApi> {
+    Whitelist(OptionalValue<MerkleProofArg<M>>),
+    /// A signed voucher replaces the whitelist check
+    Voucher,
+}
 
 #[elrond_wasm::module]
 pub trait NftMintingModule:
     crate::common_storage::CommonStorageModule
     + crate::nft_tier::NftTierModule
+    + crate::mint_phases::MintPhasesModule
+    + crate::wallet_limits::WalletLimitsModule
+    + crate::merkle_whitelist::MerkleWhitelistModule
+    + crate::mint_vouchers::MintVouchersModule
+    + crate::mystery_tier::MysteryTierModule
+    + crate::nft_reveal::NftRevealModule
+    + crate::nft_redemption::NftRedemptionModule
+    + crate::nft_fusion::NftFusionModule
+    + crate::pause::PauseModule
+    + crate::pricing::PricingModule
     + crate::royalties::RoyaltiesModule
     + crate::admin_whitelist::AdminWhitelistModule
     + crate::nft_attributes_builder::NftAttributesBuilderModule
@@ -24,6 +49,96 @@ pub trait NftMintingModule:
         brand_id: BrandId<Self::Api>,
         tier: TierName<Self::Api>,
         opt_nfts_to_buy: OptionalValue<usize>,
+        opt_whitelist_proof: OptionalValue<MerkleProofArg<Self::Api>>,
+    ) -> PaymentsVec<Self::Api> {
+        require!(
+            self.mint_voucher_signer(&brand_id).is_empty(),
+            "Brand requires a mint voucher"
+        );
+
+        let nfts_to_buy = match opt_nfts_to_buy {
+            OptionalValue::Some(val) => val,
+            OptionalValue::None => NFT_AMOUNT as usize,
+        };
+
+        self.buy_random_nft_common(
+            brand_id,
+            tier,
+            self.blockchain().get_caller(),
+            nfts_to_buy,
+            MintAuthorization::Whitelist(opt_whitelist_proof),
+        )
+    }
+
+    /// Same as `buyRandomNft`, but the NFTs are sent to `recipient`.
+    /// See `setGiftLimitsParty` for whose whitelist status and wallet limits are checked.
+    #[payable("*")]
+    #[endpoint(buyRandomNftFor)]
+    fn buy_random_nft_for(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        recipient: ManagedAddress,
+        opt_nfts_to_buy: OptionalValue<usize>,
+        opt_whitelist_proof: OptionalValue<MerkleProofArg<Self::Api>>,
+    ) -> PaymentsVec<Self::Api> {
+        require!(
+            self.mint_voucher_signer(&brand_id).is_empty(),
+            "Brand requires a mint voucher"
+        );
+
+        let nfts_to_buy = match opt_nfts_to_buy {
+            OptionalValue::Some(val) => val,
+            OptionalValue::None => NFT_AMOUNT as usize,
+        };
+
+        self.buy_random_nft_common(
+            brand_id,
+            tier,
+            recipient,
+            nfts_to_buy,
+            MintAuthorization::Whitelist(opt_whitelist_proof),
+        )
+    }
+
+    #[payable("*")]
+    #[endpoint(buyRandomNftWithVoucher)]
+    fn buy_random_nft_with_voucher(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        nfts_to_buy: usize,
+        voucher_nonce: u64,
+        voucher_expire_timestamp: u64,
+        signature: VoucherSignature<Self::Api>,
+    ) -> PaymentsVec<Self::Api> {
+        let voucher = MintVoucher {
+            minter_address: self.blockchain().get_sc_address(),
+            caller: self.blockchain().get_caller(),
+            brand_id,
+            tier,
+            nfts_to_buy,
+            nonce: voucher_nonce,
+            expire_timestamp: voucher_expire_timestamp,
+        };
+        self.require_valid_mint_voucher(&voucher, &signature);
+
+        self.buy_random_nft_common(
+            voucher.brand_id,
+            voucher.tier,
+            voucher.caller,
+            nfts_to_buy,
+            MintAuthorization::Voucher,
+        )
+    }
+
+    fn buy_random_nft_common(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        recipient: ManagedAddress,
+        nfts_to_buy: usize,
+        authorization: MintAuthorization<Self::Api>,
     ) -> PaymentsVec<Self::Api> {
         require!(
             self.registered_brands().contains(&brand_id),
@@ -33,34 +148,343 @@ pub trait NftMintingModule:
             self.nft_tiers_for_brand(&brand_id).contains(&tier),
             INVALID_TIER_ERR_MSG
         );
+        self.require_not_paused(&brand_id, &tier);
+        require!(!self.mint_closed(&brand_id).get(), "Mint closed");
 
-        let nfts_to_buy = match opt_nfts_to_buy {
-            OptionalValue::Some(val) => {
-                if val == 0 {
-                    return PaymentsVec::new();
-                }
+        if nfts_to_buy == 0 {
+            return PaymentsVec::new();
+        }
 
-                let max_nfts_per_transaction = self.max_nfts_per_transaction().get();
-                require!(
-                    val <= max_nfts_per_transaction,
-                    "Max NFTs per transaction limit exceeded"
-                );
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        let (opt_active_phase, whitelist_required) =
+            self.get_mint_phase_for_buy(&brand_id, &brand_info, nfts_to_buy);
+
+        let (payment, opt_gate_payment) = self.get_gated_payments(&brand_id, &opt_active_phase);
+        let total_price = self.get_price_quote_for_token(
+            &brand_id,
+            &tier,
+            &opt_active_phase,
+            nfts_to_buy,
+            &payment.token_identifier,
+        );
+        let payment = self.take_mint_payment(payment, &total_price);
+
+        let caller = self.blockchain().get_caller();
+        self.require_valid_mint_recipient(&caller);
+
+        let limits_address = match self.gift_limits_party(&brand_id).get() {
+            GiftLimitsParty::Payer => &caller,
+            GiftLimitsParty::Recipient => &recipient,
+        };
+        self.authorize_buy(
+            &brand_id,
+            Some(&tier),
+            limits_address,
+            &opt_active_phase,
+            whitelist_required,
+            authorization,
+            nfts_to_buy,
+        );
+        self.add_minted_count_for_address(&brand_id, &tier, limits_address, nfts_to_buy);
+
+        self.add_tier_payment(
+            &brand_id,
+            &tier,
+            &opt_active_phase,
+            &caller,
+            nfts_to_buy,
+            payment,
+        );
+
+        let output_payments =
+            self.mint_and_send_random_nft(&recipient, &brand_id, &tier, &brand_info, nfts_to_buy);
+
+        if let Some(gate_payment) = opt_gate_payment {
+            self.send().direct_esdt(
+                &caller,
+                &gate_payment.token_identifier,
+                gate_payment.token_nonce,
+                &gate_payment.amount,
+            );
+        }
+
+        self.nft_bought_event(&caller, &recipient, &brand_id, &tier, nfts_to_buy);
+
+        output_payments
+    }
+
+    /// Buys from several tiers of the brand at once, paying with one ESDT transfer per tier,
+    /// in the same order as `tier_nfts_pairs`.
+    /// Brands with a Merkle whitelist may not be bought this way while the whitelist is required.
+    #[payable("*")]
+    #[endpoint(buyRandomNftsMultiTier)]
+    fn buy_random_nfts_multi_tier(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier_nfts_pairs: MultiValueEncoded<MultiValue2<TierName<Self::Api>, usize>>,
+    ) -> PaymentsVec<Self::Api> {
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.mint_voucher_signer(&brand_id).is_empty(),
+            "Brand requires a mint voucher"
+        );
+        require!(!self.mint_closed(&brand_id).get(), "Mint closed");
 
-                val
+        let payments = self.call_value().all_esdt_transfers();
+        require!(
+            payments.len() == tier_nfts_pairs.len(),
+            "Must send one payment per tier"
+        );
+        require!(
+            tier_nfts_pairs.len() <= MAX_TIERS_PER_BRAND,
+            "Max tiers per brand limit exceeded"
+        );
+
+        let mut tier_nfts = ArrayVec::<(TierName<Self::Api>, usize), MAX_TIERS_PER_BRAND>::new();
+        let mut total_nfts_to_buy = 0;
+        for pair in tier_nfts_pairs {
+            let (tier, nfts_to_buy) = pair.into_tuple();
+            require!(
+                self.nft_tiers_for_brand(&brand_id).contains(&tier),
+                INVALID_TIER_ERR_MSG
+            );
+            self.require_not_paused(&brand_id, &tier);
+            require!(nfts_to_buy > 0, "Must buy at least one NFT per tier");
+
+            total_nfts_to_buy += nfts_to_buy;
+            unsafe {
+                tier_nfts.push_unchecked((tier, nfts_to_buy));
             }
+        }
+
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        let (opt_active_phase, whitelist_required) =
+            self.get_mint_phase_for_buy(&brand_id, &brand_info, total_nfts_to_buy);
+        self.require_not_token_gated(&brand_id, &opt_active_phase);
+
+        let caller = self.blockchain().get_caller();
+        self.require_valid_mint_recipient(&caller);
+        self.authorize_buy(
+            &brand_id,
+            None,
+            &caller,
+            &opt_active_phase,
+            whitelist_required,
+            MintAuthorization::Whitelist(OptionalValue::None),
+            total_nfts_to_buy,
+        );
+
+        let mut output_payments = PaymentsVec::new();
+        for ((tier, nfts_to_buy), payment) in tier_nfts.into_iter().zip(payments.iter()) {
+            let total_price = self.get_price_quote_for_token(
+                &brand_id,
+                &tier,
+                &opt_active_phase,
+                nfts_to_buy,
+                &EgldOrEsdtTokenIdentifier::esdt(payment.token_identifier.clone()),
+            );
+            let payment = self.take_mint_payment(payment.into(), &total_price);
+
+            self.add_minted_count_for_address(&brand_id, &tier, &caller, nfts_to_buy);
+            self.add_tier_payment(
+                &brand_id,
+                &tier,
+                &opt_active_phase,
+                &caller,
+                nfts_to_buy,
+                payment,
+            );
+
+            let nft_payments = self.mint_random_nfts(&brand_id, &tier, &brand_info, nfts_to_buy);
+            output_payments.append_vec(nft_payments);
+
+            self.nft_bought_event(&caller, &caller, &brand_id, &tier, nfts_to_buy);
+        }
+
+        self.send().direct_multi(&caller, &output_payments);
+
+        output_payments
+    }
+
+    /// Each NFT's tier is picked at random, see `setMysteryTier`.
+    /// Returns the tier of each NFT along with the NFT.
+    #[payable("*")]
+    #[endpoint(buyMysteryTierNft)]
+    fn buy_mystery_tier_nft(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        opt_nfts_to_buy: OptionalValue<usize>,
+        opt_whitelist_proof: OptionalValue<MerkleProofArg<Self::Api>>,
+    ) -> MultiValueEncoded<MultiValue2<TierName<Self::Api>, EsdtTokenPayment<Self::Api>>> {
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.mint_voucher_signer(&brand_id).is_empty(),
+            "Brand requires a mint voucher"
+        );
+        self.require_brand_not_paused(&brand_id);
+        require!(!self.mint_closed(&brand_id).get(), "Mint closed");
+
+        let price_mapper = self.mystery_tier_price(&brand_id);
+        require!(!price_mapper.is_empty(), "Brand has no mystery tier");
+
+        let nfts_to_buy = match opt_nfts_to_buy {
+            OptionalValue::Some(val) => val,
             OptionalValue::None => NFT_AMOUNT as usize,
         };
+        let mut result = MultiValueEncoded::new();
+        if nfts_to_buy == 0 {
+            return result;
+        }
 
-        let price_for_tier = self.price_for_tier(&brand_id, &tier).get();
-        let payment = self.call_value().egld_or_single_esdt();
-        let total_required_amount = &price_for_tier.amount * (nfts_to_buy as u32);
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        let (opt_active_phase, whitelist_required) =
+            self.get_mint_phase_for_buy(&brand_id, &brand_info, nfts_to_buy);
+        self.require_not_token_gated(&brand_id, &opt_active_phase);
+
+        let mut total_price = price_mapper.get();
+        total_price.amount *= nfts_to_buy as u32;
+        let payment = self.require_mint_payment(&total_price);
+
+        let caller = self.blockchain().get_caller();
+        self.require_valid_mint_recipient(&caller);
+        self.authorize_buy(
+            &brand_id,
+            None,
+            &caller,
+            &opt_active_phase,
+            whitelist_required,
+            MintAuthorization::Whitelist(opt_whitelist_proof),
+            nfts_to_buy,
+        );
+
+        self.add_mint_payment(payment.token_identifier, payment.amount);
+
+        let mut output_payments = PaymentsVec::new();
+        let mut tier_counts = ArrayVec::<(TierName<Self::Api>, usize), MAX_TIERS_PER_BRAND>::new();
+        for _ in 0..nfts_to_buy {
+            let tier = self.pick_mystery_tier(&brand_id, &caller);
+            self.add_minted_count_for_address(&brand_id, &tier, &caller, NFT_AMOUNT as usize);
+
+            let nft_payments =
+                self.mint_random_nfts(&brand_id, &tier, &brand_info, NFT_AMOUNT as usize);
+            for nft_payment in &nft_payments {
+                result.push((tier.clone(), nft_payment.clone()).into());
+            }
+            output_payments.append_vec(nft_payments);
+
+            match tier_counts
+                .iter_mut()
+                .find(|(counted_tier, _)| *counted_tier == tier)
+            {
+                Some((_, count)) => *count += NFT_AMOUNT as usize,
+                None => unsafe {
+                    tier_counts.push_unchecked((tier, NFT_AMOUNT as usize));
+                },
+            }
+        }
+
+        self.send().direct_multi(&caller, &output_payments);
+
+        for (tier, count) in &tier_counts {
+            self.nft_bought_event(&caller, &caller, &brand_id, tier, *count);
+        }
+
+        result
+    }
+
+    /// Exchanges NFTs of the brand's source collection for random NFTs of the brand,
+    /// see `setRedemptionConfig`. Mint periods, phases, prices and wallet limits don't apply.
+    #[payable("*")]
+    #[endpoint(redeemNfts)]
+    fn redeem_nfts(&self, brand_id: BrandId<Self::Api>) -> PaymentsVec<Self::Api> {
+        let config_mapper = self.redemption_config(&brand_id);
+        require!(!config_mapper.is_empty(), "Brand has no redemption");
+
+        let config = config_mapper.get();
+        self.require_not_paused(&brand_id, &config.tier);
+        require!(!self.mint_closed(&brand_id).get(), "Mint closed");
+
+        let caller = self.blockchain().get_caller();
+        let nfts_to_mint = self.take_redemption_payments(&brand_id, &config, &caller);
         require!(
-            payment.token_identifier == price_for_tier.token_id
-                && payment.amount == total_required_amount,
-            "Invalid payment"
+            nfts_to_mint <= self.max_nfts_per_transaction().get(),
+            "Max NFTs per transaction limit exceeded"
         );
 
         let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        let output_payments = self.mint_and_send_random_nft(
+            &caller,
+            &brand_id,
+            &config.tier,
+            &brand_info,
+            nfts_to_mint,
+        );
+
+        self.nfts_redeemed_event(
+            &caller,
+            &brand_id,
+            &config.tier,
+            &config.source_token_id,
+            nfts_to_mint,
+        );
+
+        output_payments
+    }
+
+    /// Exchanges NFTs of one tier, e.g. a lower one, for one random NFT of another tier of the brand,
+    /// see `setFusionRecipe`
+    #[payable("*")]
+    #[endpoint(fuseNfts)]
+    fn fuse_nfts(&self, brand_id: BrandId<Self::Api>) -> PaymentsVec<Self::Api> {
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(!self.mint_closed(&brand_id).get(), "Mint closed");
+
+        let (input_tier, recipe) = self.take_fusion_inputs(&brand_id);
+        self.require_not_paused(&brand_id, &recipe.output_tier);
+
+        let caller = self.blockchain().get_caller();
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        let output_payments = self.mint_and_send_random_nft(
+            &caller,
+            &brand_id,
+            &recipe.output_tier,
+            &brand_info,
+            FUSION_OUTPUT_NFTS,
+        );
+
+        self.nfts_fused_event(
+            &caller,
+            &brand_id,
+            &input_tier,
+            &recipe.output_tier,
+            recipe.input_nfts,
+        );
+
+        output_payments
+    }
+
+    /// Returns the active phase, if any, and whether the whitelist is required
+    fn get_mint_phase_for_buy(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        brand_info: &BrandInfo<Self::Api>,
+        nfts_to_buy: usize,
+    ) -> (Option<MintPhase<Self::Api>>, bool) {
+        let max_nfts_per_transaction = self.max_nfts_per_transaction().get();
+        require!(
+            nfts_to_buy <= max_nfts_per_transaction,
+            "Max NFTs per transaction limit exceeded"
+        );
+
         let current_timestamp = self.blockchain().get_block_timestamp();
         require!(
             current_timestamp >= brand_info.mint_period.start,
@@ -71,22 +495,111 @@ pub trait NftMintingModule:
             "May not mint after deadline"
         );
 
-        let caller = self.blockchain().get_caller();
-        if current_timestamp < brand_info.whitelist_expire_timestamp {
-            require!(
-                self.mint_whitelist(&brand_id).contains(&caller),
-                "Not in whitelist"
+        let opt_active_phase = self.get_active_mint_phase(brand_id);
+        let whitelist_required = match &opt_active_phase {
+            Some(phase) => phase.whitelist_only,
+            None => {
+                require!(
+                    self.mint_phases(brand_id).is_empty(),
+                    "No active mint phase"
+                );
+
+                current_timestamp < brand_info.whitelist_expire_timestamp
+            }
+        };
+
+        (opt_active_phase, whitelist_required)
+    }
+
+    fn require_mint_payment(
+        &self,
+        total_price: &MintPrice<Self::Api>,
+    ) -> EgldOrEsdtTokenPayment<Self::Api> {
+        let payment = self.call_value().egld_or_single_esdt();
+
+        self.take_mint_payment(payment, total_price)
+    }
+
+    /// Anything paid above `total_price` is sent back to the caller.
+    /// Returns the payment reduced to `total_price`.
+    fn take_mint_payment(
+        &self,
+        mut payment: EgldOrEsdtTokenPayment<Self::Api>,
+        total_price: &MintPrice<Self::Api>,
+    ) -> EgldOrEsdtTokenPayment<Self::Api> {
+        require!(
+            payment.token_identifier == total_price.token_id
+                && payment.amount >= total_price.amount,
+            "Invalid payment"
+        );
+
+        if payment.amount > total_price.amount {
+            let excess = &payment.amount - &total_price.amount;
+            let caller = self.blockchain().get_caller();
+            self.send().direct(
+                &caller,
+                &payment.token_identifier,
+                payment.token_nonce,
+                &excess,
             );
+
+            payment.amount = total_price.amount.clone();
         }
 
-        self.add_mint_payment(payment.token_identifier, payment.amount);
+        payment
+    }
 
-        let output_payments =
-            self.mint_and_send_random_nft(&caller, &brand_id, &tier, &brand_info, nfts_to_buy);
+    /// Without a tier, only the brand-wide whitelist is checked
+    fn authorize_buy(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        opt_tier: Option<&TierName<Self::Api>>,
+        caller: &ManagedAddress,
+        opt_active_phase: &Option<MintPhase<Self::Api>>,
+        whitelist_required: bool,
+        authorization: MintAuthorization<Self::Api>,
+        nfts_to_buy: usize,
+    ) {
+        if whitelist_required {
+            if let MintAuthorization::Whitelist(opt_proof) = authorization {
+                self.require_whitelisted(brand_id, opt_tier, caller, opt_proof, nfts_to_buy);
+            }
+        }
+
+        if let Some(phase) = opt_active_phase {
+            self.add_minted_in_phase(brand_id, phase, caller, nfts_to_buy);
+        }
+    }
 
-        self.nft_bought_event(&caller, &brand_id, &tier, nfts_to_buy);
+    fn require_whitelisted(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        opt_tier: Option<&TierName<Self::Api>>,
+        address: &ManagedAddress,
+        opt_proof: OptionalValue<MerkleProofArg<Self::Api>>,
+        nfts_to_mint: usize,
+    ) {
+        if !self.whitelist_merkle_root(brand_id).is_empty() {
+            self.require_valid_merkle_proof(brand_id, address, opt_proof, nfts_to_mint);
+            return;
+        }
 
-        output_payments
+        let whitelist_tier = match self.get_whitelist_tier(brand_id, opt_tier, address) {
+            Some(tier) => tier,
+            None => sc_panic!("Not in whitelist"),
+        };
+
+        let quota = self
+            .whitelist_quota(brand_id, &whitelist_tier, address)
+            .get();
+        let minted_mapper = self.whitelist_minted(brand_id, &whitelist_tier, address);
+        let total_minted = minted_mapper.get() + nfts_to_mint;
+        require!(
+            quota == 0 || total_minted <= quota,
+            "Whitelist allowance exceeded"
+        );
+
+        minted_mapper.set(total_minted);
     }
 
     #[endpoint(giveawayNfts)]
@@ -106,6 +619,8 @@ pub trait NftMintingModule:
             self.nft_tiers_for_brand(&brand_id).contains(&tier),
             INVALID_TIER_ERR_MSG
         );
+        self.require_not_paused(&brand_id, &tier);
+        require!(!self.mint_closed(&brand_id).get(), "Mint closed");
 
         let brand_info = self.brand_info(&brand_id).get();
         let mut total = 0;
@@ -126,6 +641,74 @@ pub trait NftMintingModule:
         self.nft_giveaway_event(&brand_id, &tier, total);
     }
 
+    /// Ends the brand's mint for good. The remaining NFTs are minted to `treasury` if given,
+    /// otherwise their IDs are retired.
+    /// Must be called again as long as it returns "interrupted".
+    #[endpoint(closeMint)]
+    fn close_mint(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        opt_treasury: OptionalValue<ManagedAddress>,
+    ) -> OperationCompletionStatus {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.mint_closed(&brand_id).set(true);
+
+        let opt_treasury = opt_treasury.into_option();
+        let gas_per_nft = match opt_treasury {
+            Some(_) => TREASURY_MINT_GAS_PER_NFT,
+            None => RETIRE_GAS_PER_NFT,
+        };
+
+        let brand_info = self.brand_info(&brand_id).get();
+        let mut status = OperationCompletionStatus::Completed;
+        let mut nfts_processed = 0;
+        for tier in self.nft_tiers_for_brand(&brand_id).iter() {
+            let available_nfts = self.get_available_nfts(&brand_id, &tier);
+            if available_nfts == 0 {
+                continue;
+            }
+
+            let gas_left = self.blockchain().get_gas_left();
+            let max_nfts_for_gas = gas_left.saturating_sub(CLOSE_MINT_GAS_RESERVE) / gas_per_nft;
+            let nfts_in_batch = core::cmp::min(available_nfts as u64, max_nfts_for_gas) as usize;
+            if nfts_in_batch > 0 {
+                match &opt_treasury {
+                    Some(treasury) => {
+                        let _ = self.mint_and_send_random_nft(
+                            treasury,
+                            &brand_id,
+                            &tier,
+                            &brand_info,
+                            nfts_in_batch,
+                        );
+                    }
+                    None => {
+                        for _ in 0..nfts_in_batch {
+                            let _ = self.get_next_random_id(&brand_id, &tier);
+                        }
+                    }
+                }
+
+                nfts_processed += nfts_in_batch;
+            }
+
+            if nfts_in_batch < available_nfts {
+                status = OperationCompletionStatus::InterruptedBeforeOutOfGas;
+                break;
+            }
+        }
+
+        let treasury = opt_treasury.unwrap_or_else(ManagedAddress::zero);
+        self.mint_closed_event(&brand_id, &treasury, nfts_processed);
+
+        status
+    }
+
     fn mint_and_send_random_nft(
         &self,
         to: &ManagedAddress,
@@ -134,36 +717,93 @@ pub trait NftMintingModule:
         brand_info: &BrandInfo<Self::Api>,
         nfts_to_send: usize,
     ) -> PaymentsVec<Self::Api> {
+        self.require_valid_mint_recipient(to);
+
+        let nft_output_payments = self.mint_random_nfts(brand_id, tier, brand_info, nfts_to_send);
+        self.send().direct_multi(to, &nft_output_payments);
+
+        nft_output_payments
+    }
+
+    /// Lets the given smart contracts (e.g. multisigs, staking or DAO contracts)
+    /// buy and receive NFTs, which is otherwise only allowed for user accounts
+    #[only_owner]
+    #[endpoint(addAllowedScRecipients)]
+    fn add_allowed_sc_recipients(&self, addresses: MultiValueEncoded<ManagedAddress>) {
+        let mut mapper = self.allowed_sc_recipients();
+        for address in addresses {
+            let _ = mapper.insert(address);
+        }
+    }
+
+    #[only_owner]
+    #[endpoint(removeAllowedScRecipients)]
+    fn remove_allowed_sc_recipients(&self, addresses: MultiValueEncoded<ManagedAddress>) {
+        let mut mapper = self.allowed_sc_recipients();
+        for address in addresses {
+            let _ = mapper.swap_remove(&address);
+        }
+    }
+
+    fn require_valid_mint_recipient(&self, to: &ManagedAddress) {
         require!(
-            !self.blockchain().is_smart_contract(to),
+            !self.blockchain().is_smart_contract(to) || self.allowed_sc_recipients().contains(to),
             "Only user accounts are allowed to mint"
         );
+    }
 
-        let total_available_nfts = self.available_ids(brand_id, tier).len();
+    fn mint_random_nfts(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        brand_info: &BrandInfo<Self::Api>,
+        nfts_to_send: usize,
+    ) -> PaymentsVec<Self::Api> {
+        let total_available_nfts = self.get_available_nfts(brand_id, tier);
         require!(
             nfts_to_send <= total_available_nfts,
             "Not enough NFTs available"
         );
 
+        require!(
+            !self.needs_token_role_confirmation(brand_id).get(),
+            "Token roles not confirmed"
+        );
+
         let nft_token_id = self.nft_token(brand_id).get_token_id();
+
+        let uri_templates = self.get_uri_templates(brand_id);
         let mut nft_output_payments = ManagedVec::new();
         for _ in 0..nfts_to_send {
             let nft_id = self.get_next_random_id(brand_id, tier);
-            let nft_uri = self.build_nft_main_file_uri(
-                &brand_info.collection_hash,
-                nft_id,
-                &brand_info.media_type,
-            );
-            let nft_json = self.build_nft_json_file_uri(&brand_info.collection_hash, nft_id);
-            let collection_json = self.build_collection_json_file_uri(&brand_info.collection_hash);
+            let opt_extension_brand_info = self
+                .get_extension_collection_hash(brand_id, tier, nft_id)
+                .map(|collection_hash| BrandInfo {
+                    collection_hash,
+                    ..brand_info.clone()
+                });
+            let nft_brand_info = opt_extension_brand_info.as_ref().unwrap_or(brand_info);
 
-            let mut uris = ManagedVec::new();
-            uris.push(nft_uri);
-            uris.push(nft_json);
-            uris.push(collection_json);
-
-            let attributes =
-                self.build_nft_attributes(&brand_info.collection_hash, brand_id, nft_id);
+            let (uris, attributes) = if brand_info.is_revealed {
+                (
+                    self.build_nft_uris(&uri_templates, nft_brand_info, nft_id),
+                    self.build_nft_attributes(
+                        &uri_templates,
+                        &nft_brand_info.collection_hash,
+                        brand_id,
+                        nft_id,
+                    ),
+                )
+            } else {
+                (
+                    self.build_placeholder_nft_uris(&uri_templates, &brand_info.collection_hash),
+                    self.build_placeholder_nft_attributes(
+                        &uri_templates,
+                        &brand_info.collection_hash,
+                        brand_id,
+                    ),
+                )
+            };
             let nft_amount = BigUint::from(NFT_AMOUNT);
             let nft_nonce = self.send().esdt_nft_create(
                 &nft_token_id,
@@ -175,6 +815,11 @@ pub trait NftMintingModule:
                 &uris,
             );
 
+            if !brand_info.is_revealed {
+                self.unrevealed_nft_id(brand_id, nft_nonce).set(nft_id);
+            }
+            self.tier_for_nonce(brand_id, nft_nonce).set(tier);
+
             nft_output_payments.push(EsdtTokenPayment::new(
                 nft_token_id.clone(),
                 nft_nonce,
@@ -182,8 +827,10 @@ pub trait NftMintingModule:
             ));
         }
 
-        self.send().direct_multi(to, &nft_output_payments);
-
         nft_output_payments
     }
+
+    #[view(getAllowedScRecipients)]
+    #[storage_mapper("allowedScRecipients")]
+    fn allowed_sc_recipients(&self) -> UnorderedSetMapper<ManagedAddress>;
 }
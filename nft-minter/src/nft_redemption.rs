@@ -0,0 +1,159 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    brand_creation::{INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG},
+    common_storage::BrandId,
+    nft_tier::TierName,
+};
+
+/// `source_nfts_per_redemption` NFTs of `source_token_id` are exchanged for
+/// `nfts_per_redemption` random NFTs of `tier`, e.g. 2 old for 1 new.
+/// The source NFTs are burned if `burn_source_nfts` is set, otherwise they are locked in the SC.
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Debug)]
+pub struct RedemptionConfig<M: ManagedTypeApi> {
+    pub source_token_id: TokenIdentifier<M>,
+    pub tier: TierName<M>,
+    pub source_nfts_per_redemption: usize,
+    pub nfts_per_redemption: usize,
+    pub burn_source_nfts: bool,
+}
+
+#[elrond_wasm::module]
+pub trait NftRedemptionModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+{
+    /// Lets holders of `source_token_id` redeem them for NFTs of the brand, see `redeemNfts`.
+    /// Burning requires the SC to have the NFT burn role for the source token.
+    #[endpoint(setRedemptionConfig)]
+    fn set_redemption_config(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        source_token_id: TokenIdentifier,
+        tier: TierName<Self::Api>,
+        source_nfts_per_redemption: usize,
+        nfts_per_redemption: usize,
+        burn_source_nfts: bool,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+        require!(
+            source_token_id.is_valid_esdt_identifier(),
+            "Invalid token ID"
+        );
+        require!(
+            source_token_id != self.nft_token(&brand_id).get_token_id(),
+            "May not redeem the brand's own NFTs"
+        );
+        require!(
+            source_nfts_per_redemption > 0 && nfts_per_redemption > 0,
+            "Invalid exchange ratio"
+        );
+
+        self.redemption_config(&brand_id).set(&RedemptionConfig {
+            source_token_id,
+            tier,
+            source_nfts_per_redemption,
+            nfts_per_redemption,
+            burn_source_nfts,
+        });
+    }
+
+    #[endpoint(clearRedemptionConfig)]
+    fn clear_redemption_config(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+
+        self.redemption_config(&brand_id).clear();
+    }
+
+    /// Burns or locks the source NFTs, depending on the brand's config.
+    /// Returns the number of NFTs to mint in exchange.
+    fn take_redemption_payments(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        config: &RedemptionConfig<Self::Api>,
+        caller: &ManagedAddress,
+    ) -> usize {
+        let payments = self.call_value().all_esdt_transfers();
+        let mut source_nfts: usize = 0;
+        for payment in &payments {
+            require!(
+                payment.token_identifier == config.source_token_id && payment.token_nonce > 0,
+                "Invalid payment"
+            );
+
+            let amount = match payment.amount.to_u64() {
+                Some(amount) => amount,
+                None => sc_panic!("Invalid payment amount"),
+            };
+            require!(amount <= usize::MAX as u64, "Invalid payment amount");
+
+            source_nfts = match source_nfts.checked_add(amount as usize) {
+                Some(total) => total,
+                None => sc_panic!("Invalid number of source NFTs"),
+            };
+        }
+        require!(
+            source_nfts > 0 && source_nfts % config.source_nfts_per_redemption == 0,
+            "Invalid number of source NFTs"
+        );
+
+        if config.burn_source_nfts {
+            let roles = self
+                .blockchain()
+                .get_esdt_local_roles(&config.source_token_id);
+            require!(
+                roles.has_role(&EsdtLocalRole::NftBurn),
+                "NFT burn role not set"
+            );
+
+            for payment in &payments {
+                self.send().esdt_local_burn(
+                    &payment.token_identifier,
+                    payment.token_nonce,
+                    &payment.amount,
+                );
+            }
+        }
+
+        self.redeemed_source_nfts(brand_id)
+            .update(|redeemed| *redeemed += source_nfts);
+        self.redeemed_source_nfts_for_address(brand_id, caller)
+            .update(|redeemed| *redeemed += source_nfts);
+
+        match (source_nfts / config.source_nfts_per_redemption)
+            .checked_mul(config.nfts_per_redemption)
+        {
+            Some(nfts_to_mint) => nfts_to_mint,
+            None => sc_panic!("Invalid number of source NFTs"),
+        }
+    }
+
+    #[view(getRedemptionConfig)]
+    #[storage_mapper("redemptionConfig")]
+    fn redemption_config(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> SingleValueMapper<RedemptionConfig<Self::Api>>;
+
+    #[view(getRedeemedSourceNfts)]
+    #[storage_mapper("redeemedSourceNfts")]
+    fn redeemed_source_nfts(&self, brand_id: &BrandId<Self::Api>) -> SingleValueMapper<usize>;
+
+    #[view(getRedeemedSourceNftsForAddress)]
+    #[storage_mapper("redeemedSourceNftsForAddress")]
+    fn redeemed_source_nfts_for_address(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
+}
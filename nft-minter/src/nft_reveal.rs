@@ -0,0 +1,114 @@
+elrond_wasm::imports!();
+
+use crate::{
+    brand_creation::INVALID_BRAND_ID_ERR_MSG,
+    common_storage::{BrandId, BrandInfo, PaymentsVec},
+    nft_attributes_builder::CollectionHash,
+};
+
+#[elrond_wasm::module]
+pub trait NftRevealModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_attributes_builder::NftAttributesBuilderModule
+    + crate::events::EventsModule
+{
+    /// Replaces the placeholder hash the brand was created with by the real collection hash.
+    /// NFTs minted from now on use the real hash,
+    /// while NFTs minted before are updated through `revealNfts`.
+    #[endpoint(revealBrand)]
+    fn reveal_brand(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        collection_hash: CollectionHash<Self::Api>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        require!(
+            self.is_valid_collection_hash(&collection_hash),
+            "Invalid collection hash"
+        );
+
+        let brand_info_mapper = self.brand_info(&brand_id);
+        let mut brand_info: BrandInfo<Self::Api> = brand_info_mapper.get();
+        require!(!brand_info.is_revealed, "Brand already revealed");
+
+        let is_new_collection = self.register_collection_hash(&collection_hash);
+        require!(is_new_collection, "Collection hash already exists");
+
+        self.unregister_collection_hash(&brand_info.collection_hash);
+
+        brand_info.collection_hash = collection_hash;
+        brand_info.is_revealed = true;
+        brand_info_mapper.set(&brand_info);
+
+        self.brand_revealed_event(&brand_id, &brand_info.collection_hash);
+    }
+
+    /// Holders send their placeholder NFTs, which are updated
+    /// with the real attributes and URIs and then sent back.
+    /// The SC may only update NFTs it currently holds, so this can't be done in bulk.
+    /// URIs can only be appended, so a revealed NFT keeps the placeholder collection URI first,
+    /// followed by its main file, metadata and collection URIs.
+    #[payable("*")]
+    #[endpoint(revealNfts)]
+    fn reveal_nfts(&self, brand_id: BrandId<Self::Api>) -> PaymentsVec<Self::Api> {
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        require!(brand_info.is_revealed, "Brand not revealed yet");
+
+        let payments = self.call_value().all_esdt_transfers();
+        let nft_token_id = self.nft_token(&brand_id).get_token_id();
+        let roles = self.blockchain().get_esdt_local_roles(&nft_token_id);
+        require!(
+            roles.has_role(&EsdtLocalRole::NftUpdateAttributes)
+                && roles.has_role(&EsdtLocalRole::NftAddUri),
+            "NFT update attributes and add URI roles not set"
+        );
+
+        let uri_templates = self.get_uri_templates(&brand_id);
+        for payment in &payments {
+            require!(payment.token_identifier == nft_token_id, "Invalid payment");
+
+            let nft_id_mapper = self.unrevealed_nft_id(&brand_id, payment.token_nonce);
+            require!(!nft_id_mapper.is_empty(), "NFT already revealed");
+
+            let nft_id = nft_id_mapper.get();
+            nft_id_mapper.clear();
+
+            let attributes = self.build_nft_attributes(
+                &uri_templates,
+                &brand_info.collection_hash,
+                &brand_id,
+                nft_id,
+            );
+            self.send()
+                .nft_update_attributes(&nft_token_id, payment.token_nonce, &attributes);
+
+            let uris = self.build_nft_uris(&uri_templates, &brand_info, nft_id);
+            self.send()
+                .nft_add_multiple_uri(&nft_token_id, payment.token_nonce, &uris);
+        }
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_multi(&caller, &payments);
+
+        payments
+    }
+
+    #[view(getUnrevealedNftId)]
+    #[storage_mapper("unrevealedNftId")]
+    fn unrevealed_nft_id(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        nft_nonce: u64,
+    ) -> SingleValueMapper<UniqueId>;
+}
@@ -1,11 +1,22 @@
 elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
 
-use crate::common_storage::BrandId;
+use crate::{common_storage::BrandId, nft_attributes_builder::CollectionHash};
 
 pub type TierName<M> = ManagedBuffer<M>;
 
 const VEC_MAPPER_FIRST_ITEM_INDEX: usize = 1;
 pub const MAX_TIERS_PER_BRAND: usize = 5;
+pub const MAX_EXTENSIONS_PER_TIER: usize = 10;
+
+/// IDs added to a tier after the brand was created, i.e. `id_offset + 1..=id_offset + total_nfts`.
+/// Without a collection hash, the brand's hash is used.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct TierExtension<M: ManagedTypeApi> {
+    pub id_offset: usize,
+    pub total_nfts: usize,
+    pub opt_collection_hash: Option<CollectionHash<M>>,
+}
 
 #[elrond_wasm::module]
 pub trait NftTierModule {
@@ -14,15 +25,85 @@ pub trait NftTierModule {
         brand_id: &BrandId<Self::Api>,
         tier: &TierName<Self::Api>,
     ) -> UniqueId {
+        let total_available_nfts = self.get_available_nfts(brand_id, tier);
+        require!(total_available_nfts > 0, "No more NFTs available for brand");
+
+        let mut rand_index =
+            self.get_random_usize(VEC_MAPPER_FIRST_ITEM_INDEX, total_available_nfts + 1);
+
         let mut id_mapper = self.available_ids(brand_id, tier);
-        let last_id_index = id_mapper.len();
-        require!(last_id_index > 0, "No more NFTs available for brand");
+        let base_len = id_mapper.len();
+        if rand_index <= base_len {
+            let rand_id = id_mapper.swap_remove(rand_index);
+            let id_offset = self.nft_id_offset_for_tier(brand_id, tier).get();
+
+            return rand_id + id_offset;
+        }
+
+        rand_index -= base_len;
+        for (i, extension) in self.tier_extensions(brand_id, tier).iter().enumerate() {
+            let mut extension_id_mapper =
+                self.extension_available_ids(brand_id, tier, i + VEC_MAPPER_FIRST_ITEM_INDEX);
+            let extension_len = extension_id_mapper.len();
+            if rand_index <= extension_len {
+                let rand_id = extension_id_mapper.swap_remove(rand_index);
+
+                return rand_id + extension.id_offset;
+            }
+
+            rand_index -= extension_len;
+        }
+
+        sc_panic!("No more NFTs available for brand");
+    }
 
-        let rand_index = self.get_random_usize(VEC_MAPPER_FIRST_ITEM_INDEX, last_id_index + 1);
-        let rand_id = id_mapper.swap_remove(rand_index);
-        let id_offset = self.nft_id_offset_for_tier(brand_id, tier).get();
+    fn get_available_nfts(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> usize {
+        let mut available_nfts = self.available_ids(brand_id, tier).len();
+        let nr_extensions = self.tier_extensions(brand_id, tier).len();
+        for extension_index in VEC_MAPPER_FIRST_ITEM_INDEX..=nr_extensions {
+            available_nfts += self
+                .extension_available_ids(brand_id, tier, extension_index)
+                .len();
+        }
 
-        rand_id + id_offset
+        available_nfts
+    }
+
+    fn get_extension_collection_hash(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        nft_id: UniqueId,
+    ) -> Option<CollectionHash<Self::Api>> {
+        for extension in self.tier_extensions(brand_id, tier).iter() {
+            if nft_id > extension.id_offset && nft_id <= extension.id_offset + extension.total_nfts
+            {
+                return extension.opt_collection_hash;
+            }
+        }
+
+        None
+    }
+
+    /// New IDs must start after this one, so they are never reused
+    fn get_highest_nft_id(&self, brand_id: &BrandId<Self::Api>) -> UniqueId {
+        let mut highest_id = 0;
+        for tier in self.nft_tiers_for_brand(brand_id).iter() {
+            let mut base_total_nfts = self.total_nfts(brand_id, &tier).get();
+            for extension in self.tier_extensions(brand_id, &tier).iter() {
+                base_total_nfts -= extension.total_nfts;
+                highest_id = core::cmp::max(highest_id, extension.id_offset + extension.total_nfts);
+            }
+
+            let base_id_offset = self.nft_id_offset_for_tier(brand_id, &tier).get();
+            highest_id = core::cmp::max(highest_id, base_id_offset + base_total_nfts);
+        }
+
+        highest_id
     }
 
     /// range is [min, max)
@@ -53,6 +134,32 @@ pub trait NftTierModule {
         tier: &TierName<Self::Api>,
     ) -> UniqueIdMapper<Self::Api>;
 
+    #[view(getTierExtensions)]
+    #[storage_mapper("tierExtensions")]
+    fn tier_extensions(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> VecMapper<TierExtension<Self::Api>>;
+
+    #[storage_mapper("extensionAvailableIds")]
+    fn extension_available_ids(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        extension_index: usize,
+    ) -> UniqueIdMapper<Self::Api>;
+
+    /// Only recorded for NFTs minted since fusion was added
+    #[view(getTierForNonce)]
+    #[storage_mapper("tierForNonce")]
+    fn tier_for_nonce(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        nft_nonce: u64,
+    ) -> SingleValueMapper<TierName<Self::Api>>;
+
+    /// Includes the NFTs added through tier extensions
     #[storage_mapper("totalNfts")]
     fn total_nfts(
         &self,
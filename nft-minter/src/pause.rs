@@ -0,0 +1,109 @@
+elrond_wasm::imports!();
+
+use crate::{
+    brand_creation::{INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG},
+    common_storage::BrandId,
+    nft_tier::TierName,
+};
+
+#[elrond_wasm::module]
+pub trait PauseModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+    + crate::events::EventsModule
+{
+    #[endpoint(pauseContract)]
+    fn pause_contract(&self) {
+        self.require_caller_is_admin();
+
+        self.contract_paused().set(true);
+        self.paused_event(&BrandId::new(), &TierName::new());
+    }
+
+    #[endpoint(unpauseContract)]
+    fn unpause_contract(&self) {
+        self.require_caller_is_admin();
+
+        self.contract_paused().clear();
+        self.unpaused_event(&BrandId::new(), &TierName::new());
+    }
+
+    #[endpoint(pauseBrand)]
+    fn pause_brand(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.brand_paused(&brand_id).set(true);
+        self.paused_event(&brand_id, &TierName::new());
+    }
+
+    #[endpoint(unpauseBrand)]
+    fn unpause_brand(&self, brand_id: BrandId<Self::Api>) {
+        self.require_caller_is_admin();
+
+        self.brand_paused(&brand_id).clear();
+        self.unpaused_event(&brand_id, &TierName::new());
+    }
+
+    #[endpoint(pauseTier)]
+    fn pause_tier(&self, brand_id: BrandId<Self::Api>, tier: TierName<Self::Api>) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+
+        self.tier_paused(&brand_id, &tier).set(true);
+        self.paused_event(&brand_id, &tier);
+    }
+
+    #[endpoint(unpauseTier)]
+    fn unpause_tier(&self, brand_id: BrandId<Self::Api>, tier: TierName<Self::Api>) {
+        self.require_caller_is_admin();
+
+        self.tier_paused(&brand_id, &tier).clear();
+        self.unpaused_event(&brand_id, &tier);
+    }
+
+    fn require_not_paused(&self, brand_id: &BrandId<Self::Api>, tier: &TierName<Self::Api>) {
+        self.require_brand_not_paused(brand_id);
+        require!(!self.tier_paused(brand_id, tier).get(), "Tier is paused");
+    }
+
+    fn require_brand_not_paused(&self, brand_id: &BrandId<Self::Api>) {
+        require!(!self.contract_paused().get(), "Contract is paused");
+        require!(!self.brand_paused(brand_id).get(), "Brand is paused");
+    }
+
+    fn is_brand_paused(&self, brand_id: &BrandId<Self::Api>) -> bool {
+        self.contract_paused().get() || self.brand_paused(brand_id).get()
+    }
+
+    fn is_tier_paused(&self, brand_id: &BrandId<Self::Api>, tier: &TierName<Self::Api>) -> bool {
+        self.is_brand_paused(brand_id) || self.tier_paused(brand_id, tier).get()
+    }
+
+    #[view(isContractPaused)]
+    #[storage_mapper("contractPaused")]
+    fn contract_paused(&self) -> SingleValueMapper<bool>;
+
+    #[view(isBrandPaused)]
+    #[storage_mapper("brandPaused")]
+    fn brand_paused(&self, brand_id: &BrandId<Self::Api>) -> SingleValueMapper<bool>;
+
+    #[view(isTierPaused)]
+    #[storage_mapper("tierPaused")]
+    fn tier_paused(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> SingleValueMapper<bool>;
+}
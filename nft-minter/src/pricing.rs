@@ -0,0 +1,469 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    brand_creation::{
+        EXTRA_PRICE_OPTIONS_CONFLICT_ERR_MSG, INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG,
+    },
+    common_storage::{BrandId, BrandInfo, MintPrice},
+    mint_phases::MintPhase,
+    nft_tier::TierName,
+};
+
+const BASIS_POINTS: u64 = 10_000;
+
+/// The price starts at `start_price` and drops by `price_step` every `step_duration` seconds
+/// from `start_timestamp`, until it reaches `floor_price`.
+/// With `rebate_to_clearing_price`, buyers may claim back what they paid
+/// above the last sale's price once the auction is over.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct DutchAuction<M: ManagedTypeApi> {
+    pub start_price: BigUint<M>,
+    pub floor_price: BigUint<M>,
+    pub price_step: BigUint<M>,
+    pub step_duration: u64,
+    pub start_timestamp: u64,
+    pub rebate_to_clearing_price: bool,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub enum CurveKind {
+    /// `step_increase` is added to the price every step
+    Linear,
+    /// The price grows by `step_increase` basis points every step, compounded
+    Exponential,
+}
+
+/// The price starts at the tier's base price and increases
+/// every `nfts_per_step` NFTs sold (or given away) from the tier.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct BondingCurve<M: ManagedTypeApi> {
+    pub kind: CurveKind,
+    pub step_increase: BigUint<M>,
+    pub nfts_per_step: usize,
+}
+
+/// Mint phase prices always take precedence over the strategy
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub enum PricingStrategy<M: ManagedTypeApi> {
+    /// The tier's `price_for_tier`
+    Fixed,
+    DutchAuction(DutchAuction<M>),
+    BondingCurve(BondingCurve<M>),
+}
+
+#[elrond_wasm::module]
+pub trait PricingModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+    + crate::mint_phases::MintPhasesModule
+    + crate::pause::PauseModule
+    + crate::royalties::RoyaltiesModule
+    + crate::events::EventsModule
+{
+    /// The price token is still the one set for the tier.
+    /// During the mint, the tier must be paused first.
+    /// Tiers with extra price options may only use the fixed price.
+    #[endpoint(setPricingStrategy)]
+    fn set_pricing_strategy(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        strategy: PricingStrategy<Self::Api>,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(&brand_id).get();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let is_mint_active = current_timestamp >= brand_info.mint_period.start
+            && current_timestamp < brand_info.mint_period.end;
+        require!(
+            !is_mint_active || self.is_tier_paused(&brand_id, &tier),
+            "May not change price during mint unless paused"
+        );
+        require!(
+            self.auction_total_sold(&brand_id, &tier).get() == 0,
+            "May not change pricing after auction sales"
+        );
+
+        match &strategy {
+            PricingStrategy::Fixed => {
+                self.pricing_strategy_for_tier(&brand_id, &tier).clear();
+                return;
+            }
+            PricingStrategy::DutchAuction(auction) => {
+                require!(
+                    auction.start_price >= auction.floor_price,
+                    "Start price must not be lower than floor price"
+                );
+                require!(auction.step_duration > 0, "Invalid step duration");
+            }
+            PricingStrategy::BondingCurve(curve) => {
+                require!(curve.nfts_per_step > 0, "Invalid NFTs per step");
+            }
+        }
+        require!(
+            self.extra_price_options_for_tier(&brand_id, &tier)
+                .is_empty(),
+            EXTRA_PRICE_OPTIONS_CONFLICT_ERR_MSG
+        );
+
+        self.pricing_strategy_for_tier(&brand_id, &tier)
+            .set(&strategy);
+    }
+
+    /// Total price for `nfts_to_buy` NFTs bought right now
+    fn get_price_quote(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+        nfts_to_buy: usize,
+    ) -> MintPrice<Self::Api> {
+        let mut price = self.get_mint_price_for_tier(brand_id, tier, opt_phase);
+        let strategy_mapper = self.pricing_strategy_for_tier(brand_id, tier);
+        if self.has_phase_price(brand_id, tier, opt_phase) || strategy_mapper.is_empty() {
+            price.amount *= nfts_to_buy as u32;
+            return price;
+        }
+
+        price.amount = match strategy_mapper.get() {
+            PricingStrategy::Fixed => price.amount * nfts_to_buy as u32,
+            PricingStrategy::DutchAuction(auction) => {
+                self.get_dutch_auction_price(&auction) * nfts_to_buy as u32
+            }
+            PricingStrategy::BondingCurve(curve) => {
+                let total_nfts = self.total_nfts(brand_id, tier).get();
+                let nfts_sold = total_nfts - self.get_available_nfts(brand_id, tier);
+
+                self.get_bonding_curve_total(&curve, &price.amount, nfts_sold, nfts_to_buy)
+            }
+        };
+
+        price
+    }
+
+    /// Same as `get_price_quote` when paying with the tier's price token,
+    /// otherwise the tier's extra price option for `payment_token_id`, if any
+    fn get_price_quote_for_token(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+        nfts_to_buy: usize,
+        payment_token_id: &EgldOrEsdtTokenIdentifier,
+    ) -> MintPrice<Self::Api> {
+        let primary_price = self.get_price_quote(brand_id, tier, opt_phase, nfts_to_buy);
+        if &primary_price.token_id == payment_token_id {
+            return primary_price;
+        }
+
+        match self
+            .extra_price_options_for_tier(brand_id, tier)
+            .get(payment_token_id)
+        {
+            Some(amount) => MintPrice {
+                token_id: payment_token_id.clone(),
+                amount: amount * nfts_to_buy as u32,
+            },
+            None => primary_price,
+        }
+    }
+
+    /// Price of the next NFT
+    fn get_current_price_for_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+    ) -> MintPrice<Self::Api> {
+        self.get_price_quote(brand_id, tier, opt_phase, 1)
+    }
+
+    /// NFTs are grouped by curve step, as all NFTs in a step cost the same
+    fn get_bonding_curve_total(
+        &self,
+        curve: &BondingCurve<Self::Api>,
+        base_price: &BigUint,
+        nfts_sold: usize,
+        nfts_to_buy: usize,
+    ) -> BigUint {
+        let mut total = BigUint::zero();
+        let mut next_nft = nfts_sold;
+        let end_nft = nfts_sold + nfts_to_buy;
+        while next_nft < end_nft {
+            let step = next_nft / curve.nfts_per_step;
+            let step_end_nft = core::cmp::min((step + 1) * curve.nfts_per_step, end_nft);
+            let nfts_in_step = step_end_nft - next_nft;
+
+            let step_price = match curve.kind {
+                CurveKind::Linear => base_price + &(&curve.step_increase * step as u32),
+                CurveKind::Exponential => {
+                    let growth = (&curve.step_increase + BASIS_POINTS).pow(step as u32);
+                    base_price * &growth / BigUint::from(BASIS_POINTS).pow(step as u32)
+                }
+            };
+            total += step_price * nfts_in_step as u32;
+
+            next_nft = step_end_nft;
+        }
+
+        total
+    }
+
+    fn get_dutch_auction_price(&self, auction: &DutchAuction<Self::Api>) -> BigUint {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        if current_timestamp <= auction.start_timestamp {
+            return auction.start_price.clone();
+        }
+
+        let steps = (current_timestamp - auction.start_timestamp) / auction.step_duration;
+        let total_decrease = &auction.price_step * steps;
+        if total_decrease >= &auction.start_price - &auction.floor_price {
+            return auction.floor_price.clone();
+        }
+
+        &auction.start_price - &total_decrease
+    }
+
+    fn has_phase_price(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+    ) -> bool {
+        match opt_phase {
+            Some(phase) => !self
+                .phase_price_for_tier(brand_id, &phase.name, tier)
+                .is_empty(),
+            None => false,
+        }
+    }
+
+    /// Payments for rebate auctions are held until the auction is settled,
+    /// unless made with one of the tier's extra price options.
+    /// Once settled, the tier may not be sold anymore, even if its mint period or supply is extended.
+    fn add_tier_payment(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        opt_phase: &Option<MintPhase<Self::Api>>,
+        buyer: &ManagedAddress,
+        nfts_bought: usize,
+        payment: EgldOrEsdtTokenPayment<Self::Api>,
+    ) {
+        let opt_auction = self.get_rebate_auction(brand_id, tier);
+        require!(
+            opt_auction.is_none() || !self.auction_settled(brand_id, tier).get(),
+            "Auction already settled"
+        );
+
+        let is_extra_price_option =
+            payment.token_identifier != self.price_for_tier(brand_id, tier).get().token_id;
+        if opt_auction.is_none()
+            || is_extra_price_option
+            || self.has_phase_price(brand_id, tier, opt_phase)
+        {
+            self.add_mint_payment(payment.token_identifier, payment.amount);
+            return;
+        }
+
+        let unit_price = &payment.amount / nfts_bought as u32;
+        self.auction_last_price(brand_id, tier).set(&unit_price);
+        self.auction_total_sold(brand_id, tier)
+            .update(|total_sold| *total_sold += nfts_bought);
+        self.auction_paid(brand_id, tier, buyer)
+            .update(|paid| *paid += payment.amount);
+        self.auction_bought(brand_id, tier, buyer)
+            .update(|bought| *bought += nfts_bought);
+    }
+
+    /// Sends back what the caller paid above the clearing price,
+    /// i.e. the price of the last sale.
+    #[endpoint(claimAuctionRebate)]
+    fn claim_auction_rebate(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+    ) -> EgldOrEsdtTokenPayment<Self::Api> {
+        require!(
+            self.get_rebate_auction(&brand_id, &tier).is_some(),
+            "Tier has no rebate auction"
+        );
+        require!(
+            self.is_auction_finished(&brand_id, &tier),
+            "Auction not finished"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let paid_mapper = self.auction_paid(&brand_id, &tier, &caller);
+        let bought_mapper = self.auction_bought(&brand_id, &tier, &caller);
+        let total_paid = paid_mapper.get();
+        let nfts_bought = bought_mapper.get();
+        require!(nfts_bought > 0, "Nothing to claim");
+
+        paid_mapper.clear();
+        bought_mapper.clear();
+
+        let clearing_price = self.auction_last_price(&brand_id, &tier).get();
+        let rebate_amount = total_paid - clearing_price * nfts_bought as u32;
+        let token_id = self.price_for_tier(&brand_id, &tier).get().token_id;
+        if rebate_amount > 0 {
+            self.send().direct(&caller, &token_id, 0, &rebate_amount);
+        }
+
+        EgldOrEsdtTokenPayment::new(token_id, 0, rebate_amount)
+    }
+
+    /// Moves the clearing price of every NFT sold to the mint payments
+    #[endpoint(settleAuction)]
+    fn settle_auction(&self, brand_id: BrandId<Self::Api>, tier: TierName<Self::Api>) {
+        require!(
+            self.get_rebate_auction(&brand_id, &tier).is_some(),
+            "Tier has no rebate auction"
+        );
+        require!(
+            self.is_auction_finished(&brand_id, &tier),
+            "Auction not finished"
+        );
+
+        let settled_mapper = self.auction_settled(&brand_id, &tier);
+        require!(!settled_mapper.get(), "Auction already settled");
+        settled_mapper.set(true);
+
+        let clearing_price = self.auction_last_price(&brand_id, &tier).get();
+        let total_sold = self.auction_total_sold(&brand_id, &tier).get();
+        let token_id = self.price_for_tier(&brand_id, &tier).get().token_id;
+        self.add_mint_payment(token_id, clearing_price * total_sold as u32);
+    }
+
+    fn get_rebate_auction(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> Option<DutchAuction<Self::Api>> {
+        let strategy_mapper = self.pricing_strategy_for_tier(brand_id, tier);
+        if strategy_mapper.is_empty() {
+            return None;
+        }
+
+        match strategy_mapper.get() {
+            PricingStrategy::DutchAuction(auction) if auction.rebate_to_clearing_price => {
+                Some(auction)
+            }
+            _ => None,
+        }
+    }
+
+    /// Over once the tier sold out or the brand's mint ended or was closed
+    fn is_auction_finished(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> bool {
+        let brand_info: BrandInfo<Self::Api> = self.brand_info(brand_id).get();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+
+        current_timestamp >= brand_info.mint_period.end
+            || self.mint_closed(brand_id).get()
+            || self.get_available_nfts(brand_id, tier) == 0
+    }
+
+    #[view(getCurrentPrice)]
+    fn get_current_price_view(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+    ) -> MintPrice<Self::Api> {
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+
+        let active_phase = self.get_active_mint_phase(&brand_id);
+
+        self.get_current_price_for_tier(&brand_id, &tier, &active_phase)
+    }
+
+    #[view(getPriceQuote)]
+    fn get_price_quote_view(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        nfts_to_buy: usize,
+    ) -> MintPrice<Self::Api> {
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+
+        let active_phase = self.get_active_mint_phase(&brand_id);
+
+        self.get_price_quote(&brand_id, &tier, &active_phase, nfts_to_buy)
+    }
+
+    #[view(getPricingStrategy)]
+    #[storage_mapper("pricingStrategyForTier")]
+    fn pricing_strategy_for_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> SingleValueMapper<PricingStrategy<Self::Api>>;
+
+    #[view(getAuctionClearingPrice)]
+    #[storage_mapper("auctionLastPrice")]
+    fn auction_last_price(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("auctionTotalSold")]
+    fn auction_total_sold(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("auctionSettled")]
+    fn auction_settled(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> SingleValueMapper<bool>;
+
+    #[view(getAuctionPaid)]
+    #[storage_mapper("auctionPaid")]
+    fn auction_paid(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getAuctionBought)]
+    #[storage_mapper("auctionBought")]
+    fn auction_bought(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
+}
@@ -4,6 +4,8 @@ elrond_wasm::derive_imports!();
 use crate::{
     brand_creation::INVALID_BRAND_ID_ERR_MSG,
     common_storage::{BrandId, BrandInfo, MintPrice},
+    mint_phases::MintPhase,
+    nft_attributes_builder::UriTemplates,
     nft_tier::{TierName, MAX_TIERS_PER_BRAND},
 };
 
@@ -12,6 +14,10 @@ pub struct BrandInfoViewResultType<M: ManagedTypeApi> {
     pub brand_id: BrandId<M>,
     pub nft_token_id: TokenIdentifier<M>,
     pub brand_info: BrandInfo<M>,
+    pub active_phase: Option<MintPhase<M>>,
+    pub uri_templates: UriTemplates<M>,
+    pub is_paused: bool,
+    pub is_mint_closed: bool,
     pub tier_info_entries: ArrayVec<TierInfoEntry<M>, MAX_TIERS_PER_BRAND>,
 }
 
@@ -21,11 +27,22 @@ pub struct TierInfoEntry<M: ManagedTypeApi> {
     pub total_nfts: usize,
     pub available_nfts: usize,
     pub mint_price: MintPrice<M>,
+    /// `mint_price` first, then the price of one NFT in each other accepted token
+    pub price_options: ManagedVec<M, MintPrice<M>>,
+    pub is_paused: bool,
 }
 
 #[elrond_wasm::module]
 pub trait ViewsModule:
-    crate::common_storage::CommonStorageModule + crate::nft_tier::NftTierModule
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+    + crate::mint_phases::MintPhasesModule
+    + crate::nft_attributes_builder::NftAttributesBuilderModule
+    + crate::pause::PauseModule
+    + crate::pricing::PricingModule
+    + crate::royalties::RoyaltiesModule
+    + crate::events::EventsModule
 {
     #[view(getBrandInfo)]
     fn get_brand_info_view(
@@ -39,18 +56,34 @@ pub trait ViewsModule:
 
         let nft_token_id = self.nft_token(&brand_id).get_token_id();
         let brand_info = self.brand_info(&brand_id).get();
+        let active_phase = self.get_active_mint_phase(&brand_id);
+        let uri_templates = self.get_uri_templates(&brand_id);
+        let is_brand_paused = self.is_brand_paused(&brand_id);
+        let is_mint_closed = self.mint_closed(&brand_id).get();
 
         let mut tier_info_entries = ArrayVec::new();
         for tier in self.nft_tiers_for_brand(&brand_id).iter() {
             let total_nfts = self.total_nfts(&brand_id, &tier).get();
-            let available_nfts = self.available_ids(&brand_id, &tier).len();
-            let mint_price = self.price_for_tier(&brand_id, &tier).get();
+            let available_nfts = if is_mint_closed {
+                0
+            } else {
+                self.get_available_nfts(&brand_id, &tier)
+            };
+            let mint_price = self.get_current_price_for_tier(&brand_id, &tier, &active_phase);
+            let mut price_options = ManagedVec::new();
+            price_options.push(mint_price.clone());
+            for (token_id, amount) in self.extra_price_options_for_tier(&brand_id, &tier).iter() {
+                price_options.push(MintPrice { token_id, amount });
+            }
+            let is_paused = self.is_tier_paused(&brand_id, &tier);
 
             tier_info_entries.push(TierInfoEntry {
                 tier,
                 total_nfts,
                 available_nfts,
                 mint_price,
+                price_options,
+                is_paused,
             })
         }
 
@@ -58,10 +91,89 @@ pub trait ViewsModule:
             brand_id,
             nft_token_id,
             brand_info,
+            active_phase,
+            uri_templates,
+            is_paused: is_brand_paused,
+            is_mint_closed,
             tier_info_entries,
         }
     }
 
+    /// NFTs the address may still mint while the whitelist is required, `None` for no limit.
+    /// With a tier, the tier whitelist is checked first.
+    /// Does not apply to brands using a Merkle whitelist.
+    #[view(getWhitelistAllowance)]
+    fn get_whitelist_allowance(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        address: ManagedAddress,
+        opt_tier: OptionalValue<TierName<Self::Api>>,
+    ) -> Option<usize> {
+        let opt_tier = opt_tier.into_option();
+        let whitelist_tier = match self.get_whitelist_tier(&brand_id, opt_tier.as_ref(), &address) {
+            Some(tier) => tier,
+            None => return Some(0),
+        };
+
+        let quota = self
+            .whitelist_quota(&brand_id, &whitelist_tier, &address)
+            .get();
+        if quota == 0 {
+            return None;
+        }
+
+        let minted = self
+            .whitelist_minted(&brand_id, &whitelist_tier, &address)
+            .get();
+        Some(quota.saturating_sub(minted))
+    }
+
+    /// Up to `limit` addresses of the brand-wide whitelist, or of the tier's if given,
+    /// starting from position `offset`
+    #[view(getMintWhitelistPage)]
+    fn get_mint_whitelist_page(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        offset: usize,
+        limit: usize,
+        opt_tier: OptionalValue<TierName<Self::Api>>,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        let (_, whitelist_mapper) = self.get_whitelist_mapper(&brand_id, opt_tier);
+        let whitelist_len = whitelist_mapper.len();
+        let end = core::cmp::min(offset.saturating_add(limit), whitelist_len);
+
+        let mut result = MultiValueEncoded::new();
+        for index in offset..end {
+            // mapper indexes start at 1
+            result.push(whitelist_mapper.get_by_index(index + 1));
+        }
+
+        result
+    }
+
+    #[view(isWhitelisted)]
+    fn is_whitelisted(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        address: ManagedAddress,
+        opt_tier: OptionalValue<TierName<Self::Api>>,
+    ) -> bool {
+        let (_, whitelist_mapper) = self.get_whitelist_mapper(&brand_id, opt_tier);
+
+        whitelist_mapper.contains(&address)
+    }
+
+    #[view(getWhitelistSize)]
+    fn get_whitelist_size(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        opt_tier: OptionalValue<TierName<Self::Api>>,
+    ) -> usize {
+        let (_, whitelist_mapper) = self.get_whitelist_mapper(&brand_id, opt_tier);
+
+        whitelist_mapper.len()
+    }
+
     #[view(getAllBrandsInfo)]
     fn get_all_brands_info(&self) -> MultiValueEncoded<BrandInfoViewResultType<Self::Api>> {
         let mut result = MultiValueEncoded::new();
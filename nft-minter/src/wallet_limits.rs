@@ -0,0 +1,143 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    brand_creation::{INVALID_BRAND_ID_ERR_MSG, INVALID_TIER_ERR_MSG},
+    common_storage::BrandId,
+    nft_tier::TierName,
+};
+
+/// Whose whitelist status and wallet limits are checked when NFTs are bought for someone else
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Debug)]
+pub enum GiftLimitsParty {
+    Payer,
+    Recipient,
+}
+
+#[elrond_wasm::module]
+pub trait WalletLimitsModule:
+    crate::admin_whitelist::AdminWhitelistModule
+    + crate::common_storage::CommonStorageModule
+    + crate::nft_tier::NftTierModule
+{
+    /// A value of 0 removes the limit
+    #[endpoint(setMaxNftsPerWallet)]
+    fn set_max_nfts_per_wallet(&self, brand_id: BrandId<Self::Api>, max: usize) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.max_nfts_per_wallet(&brand_id).set(max);
+    }
+
+    /// A value of 0 removes the limit
+    #[endpoint(setMaxNftsPerWalletForTier)]
+    fn set_max_nfts_per_wallet_for_tier(
+        &self,
+        brand_id: BrandId<Self::Api>,
+        tier: TierName<Self::Api>,
+        max: usize,
+    ) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+        require!(
+            self.nft_tiers_for_brand(&brand_id).contains(&tier),
+            INVALID_TIER_ERR_MSG
+        );
+
+        self.max_nfts_per_wallet_for_tier(&brand_id, &tier).set(max);
+    }
+
+    #[endpoint(setGiftLimitsParty)]
+    fn set_gift_limits_party(&self, brand_id: BrandId<Self::Api>, party: GiftLimitsParty) {
+        self.require_caller_is_admin();
+        require!(
+            self.registered_brands().contains(&brand_id),
+            INVALID_BRAND_ID_ERR_MSG
+        );
+
+        self.gift_limits_party(&brand_id).set(party);
+    }
+
+    fn is_tier_wallet_limit_reached(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        address: &ManagedAddress,
+    ) -> bool {
+        let tier_max = self.max_nfts_per_wallet_for_tier(brand_id, tier).get();
+        tier_max > 0
+            && self
+                .minted_count_for_address_in_tier(brand_id, tier, address)
+                .get()
+                >= tier_max
+    }
+
+    fn add_minted_count_for_address(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        address: &ManagedAddress,
+        nfts_to_mint: usize,
+    ) {
+        let brand_count_mapper = self.minted_count_for_address(brand_id, address);
+        let brand_total = brand_count_mapper.get() + nfts_to_mint;
+        let brand_max = self.max_nfts_per_wallet(brand_id).get();
+        require!(
+            brand_max == 0 || brand_total <= brand_max,
+            "Wallet limit exceeded for brand"
+        );
+
+        let tier_count_mapper = self.minted_count_for_address_in_tier(brand_id, tier, address);
+        let tier_total = tier_count_mapper.get() + nfts_to_mint;
+        let tier_max = self.max_nfts_per_wallet_for_tier(brand_id, tier).get();
+        require!(
+            tier_max == 0 || tier_total <= tier_max,
+            "Wallet limit exceeded for tier"
+        );
+
+        brand_count_mapper.set(brand_total);
+        tier_count_mapper.set(tier_total);
+    }
+
+    #[view(getMaxNftsPerWallet)]
+    #[storage_mapper("maxNftsPerWallet")]
+    fn max_nfts_per_wallet(&self, brand_id: &BrandId<Self::Api>) -> SingleValueMapper<usize>;
+
+    #[view(getMaxNftsPerWalletForTier)]
+    #[storage_mapper("maxNftsPerWalletForTier")]
+    fn max_nfts_per_wallet_for_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+    ) -> SingleValueMapper<usize>;
+
+    #[view(getMintedCountForAddress)]
+    #[storage_mapper("mintedCountForAddress")]
+    fn minted_count_for_address(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
+
+    #[view(getMintedCountForAddressInTier)]
+    #[storage_mapper("mintedCountForAddressInTier")]
+    fn minted_count_for_address_in_tier(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+        tier: &TierName<Self::Api>,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<usize>;
+
+    #[view(getGiftLimitsParty)]
+    #[storage_mapper("giftLimitsParty")]
+    fn gift_limits_party(
+        &self,
+        brand_id: &BrandId<Self::Api>,
+    ) -> SingleValueMapper<GiftLimitsParty>;
+}
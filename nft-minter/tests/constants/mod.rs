@@ -1,5 +1,3 @@
-use nft_minter::nft_attributes_builder::COLLECTION_HASH_LEN;
-
 pub const ESDT_SYSTEM_SC_ADDRESS_ARRAY: [u8; 32] = elrond_wasm::hex_literal::hex!(
     "000000000000000000010000000000000000000000000000000000000002ffff"
 );
@@ -10,8 +8,7 @@ pub const CATEGORY: &[u8] = b"VeryCoolNfts";
 pub const EGLD_TOKEN_ID: &[u8] = b"EGLD";
 pub const MAX_NFTS_PER_TX: usize = 2;
 
-pub const FIRST_COLLECTION_HASH: &[u8; COLLECTION_HASH_LEN] =
-    b"FirstCollection_______________________________";
+pub const FIRST_COLLECTION_HASH: &[u8] = b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
 pub const FIRST_BRAND_ID: &[u8] = b"FirstBrand";
 pub const FIRST_MEDIA_TYPE: &[u8] = b"png";
 pub const FIRST_MINT_START_TIMESTAMP: u64 = 100_000_000;
@@ -25,8 +22,7 @@ pub const FIRST_TAGS: &[&[u8]] = &[b"funny", b"sad", b"memes"];
 pub const FIRST_TIERS: &[&[u8]] = &[b"gold", b"silver", b"bronze"];
 pub const FIRST_NFT_AMOUNTS: &[usize] = &[5, 10, 20];
 
-pub const SECOND_COLLECTION_HASH: &[u8; COLLECTION_HASH_LEN] =
-    b"SecondCollection______________________________";
+pub const SECOND_COLLECTION_HASH: &[u8] = b"QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o";
 pub const SECOND_BRAND_ID: &[u8] = b"SecondBrand";
 pub const SECOND_MEDIA_TYPE: &[u8] = b"mp3";
 pub const SECOND_MINT_START_TIMESTAMP: u64 = 200_000_000;
@@ -40,12 +36,12 @@ pub const SECOND_TAGS: &[&[u8]] = &[b"random", b"good", b"best"];
 pub const SECOND_TIERS: &[&[u8]] = &[b"gold", b"silver", b"bronze"];
 pub const SECOND_NFT_AMOUNTS: &[usize] = &[10, 15, 25];
 
-pub const THIRD_COLLECTION_HASH: &[u8; COLLECTION_HASH_LEN] =
-    b"ThirdCollection_______________________________";
+pub const THIRD_COLLECTION_HASH: &[u8] = b"QmPZ9gcCEpqKTo6aq61g2nXGUhM4iCL3ewB6LDXZCtioEB";
+pub const CIDV1_COLLECTION_HASH: &[u8] =
+    b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
 pub const THIRD_BRAND_ID: &[u8] = b"ThirdBrand";
 
-pub const CUSTOM_COLLECTION_HASH: &[u8; COLLECTION_HASH_LEN] =
-    b"CustomCollection______________________________";
+pub const CUSTOM_COLLECTION_HASH: &[u8] = b"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
 pub const CUSTOM_BRAND_ID: &[u8] = b"CustomBrand";
 pub const CUSTOM_MEDIA_TYPE: &[u8] = b"png";
 pub const CUSTOM_MINT_START_TIMESTAMP: u64 = 100_000_000;
@@ -60,3 +56,10 @@ pub const CUSTOM_TIERS: &[&[u8]] = &[b"gold"];
 pub const CUSTOM_NFT_AMOUNTS: &[usize] = &[3];
 pub const CUSTOM_WHITELIST_EXPIRE_TIMESTAMP: u64 = 150_000_000;
 pub const CUSTOM_ROYALTIES: u64 = 10;
+
+pub const EXISTING_TOKEN_ID: &[u8] = b"LEGACY-a1b2c3";
+
+pub const USDC_TOKEN_ID: &[u8] = b"USDC-123456";
+pub const COMMUNITY_TOKEN_ID: &[u8] = b"COMM-abcdef";
+pub const GATE_TOKEN_ID: &[u8] = b"PASS-abcdef";
+pub const OLD_COLLECTION_TOKEN_ID: &[u8] = b"OLD-123456";
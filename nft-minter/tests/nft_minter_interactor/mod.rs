@@ -1,17 +1,26 @@
 use super::constants::*;
+use ed25519_dalek::{Keypair, Signer};
 use elrond_wasm::{
+    arrayvec::ArrayVec,
     elrond_codec::multi_types::OptionalValue,
-    types::{Address, EsdtLocalRole, ManagedVec, MultiValueEncoded},
+    types::{
+        Address, EsdtLocalRole, ManagedAsyncCallError, ManagedAsyncCallResult, ManagedBuffer,
+        ManagedByteArray, ManagedVec, MultiValueEncoded, TokenIdentifier,
+    },
 };
 use elrond_wasm_debug::{
     managed_address, managed_biguint, managed_buffer, rust_biguint,
     testing_framework::{BlockchainStateWrapper, ContractObjWrapper},
-    tx_mock::TxResult,
+    tx_mock::{TxInputESDT, TxResult},
     DebugApi,
 };
-use nft_minter::brand_creation::BrandCreationModule;
-use nft_minter::nft_attributes_builder::COLLECTION_HASH_LEN;
+use nft_minter::brand_creation::{BrandCreationModule, TempCallbackStorageInfo};
+use nft_minter::common_storage::{BrandInfo, CommonStorageModule, MintPrice, TimePeriod};
+use nft_minter::mint_phases::MintPhasesModule;
 use nft_minter::nft_minting::NftMintingModule;
+use nft_minter::nft_reveal::NftRevealModule;
+use nft_minter::nft_tier::NftTierModule;
+use nft_minter::wallet_limits::WalletLimitsModule;
 use nft_minter::NftMinter;
 
 #[macro_export]
@@ -27,6 +36,31 @@ macro_rules! managed_token_id {
     }};
 }
 
+/// Arguments of `addMintPhase`, with the tier prices given as (tier, amount) pairs
+pub struct MintPhaseArgs<'a> {
+    pub name: &'a [u8],
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub whitelist_only: bool,
+    pub max_nfts_per_wallet: usize,
+    pub tier_prices: &'a [(&'a [u8], u64)],
+}
+
+/// A merkle whitelist entry's allowance, along with its proof
+pub struct WhitelistProof<'a> {
+    pub allowance: usize,
+    pub proof: &'a [[u8; 32]],
+}
+
+/// The fields of a mint voucher that are signed, besides the SC and buyer addresses
+pub struct MintVoucherArgs<'a> {
+    pub brand_id: &'a [u8],
+    pub tier: &'a [u8],
+    pub nfts_to_buy: usize,
+    pub nonce: u64,
+    pub expire_timestamp: u64,
+}
+
 pub struct NftMinterSetup<NftMinterObjBuilder>
 where
     NftMinterObjBuilder: 'static + Copy + Fn() -> nft_minter::ContractObj<DebugApi>,
@@ -96,6 +130,11 @@ where
             FIRST_TIERS,
             FIRST_NFT_AMOUNTS,
             0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
         )
         .assert_ok();
 
@@ -114,6 +153,11 @@ where
             SECOND_TIERS,
             SECOND_NFT_AMOUNTS,
             0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
         )
         .assert_ok();
 
@@ -144,7 +188,12 @@ where
             CUSTOM_TAGS,
             CUSTOM_TIERS,
             CUSTOM_NFT_AMOUNTS,
-            CUSTOM_WHITELIST_EXPIRE_TIMESTAMP
+            CUSTOM_WHITELIST_EXPIRE_TIMESTAMP,
+            0,
+            &[],
+            false,
+            None,
+            &[],
         )
         .assert_ok();
 
@@ -152,7 +201,7 @@ where
             self.nm_wrapper.address_ref(),
             CUSTOM_TOKEN_ID,
             &[EsdtLocalRole::NftCreate][..],
-        );       
+        );
     }
 
     pub fn build_nft_attributes_first_token(&self, nft_id: usize) -> String {
@@ -184,7 +233,7 @@ where
 {
     pub fn call_create_new_brand(
         &mut self,
-        collection_hash: &[u8; COLLECTION_HASH_LEN],
+        collection_hash: &[u8],
         brand_id: &[u8],
         media_type: &[u8],
         royalties: u64,
@@ -198,6 +247,11 @@ where
         tiers: &[&[u8]],
         nr_nfts_per_tier: &[usize],
         whitelist_expire_epoch: u64,
+        max_nfts_per_wallet: usize,
+        max_nfts_per_wallet_per_tier: &[usize],
+        delayed_reveal: bool,
+        opt_uri_templates: Option<(&[u8], &[u8])>,
+        extra_price_options: &[(&[u8], u64)],
     ) -> TxResult {
         self.b_mock.execute_tx(
             &self.owner_address,
@@ -213,20 +267,37 @@ where
                     panic!("Tier args length mismatch");
                 }
 
+                let mut managed_extra_price_options = ManagedVec::new();
+                for (token_id, amount) in extra_price_options {
+                    managed_extra_price_options.push(MintPrice {
+                        token_id: managed_token_id!(*token_id),
+                        amount: managed_biguint!(*amount),
+                    });
+                }
+
                 let mut tier_args = MultiValueEncoded::new();
-                for (tier, nr_nfts) in tiers.iter().zip(nr_nfts_per_tier.iter()) {
+                for (i, (tier, nr_nfts)) in tiers.iter().zip(nr_nfts_per_tier.iter()).enumerate() {
+                    let tier_max_nfts_per_wallet = max_nfts_per_wallet_per_tier
+                        .get(i)
+                        .copied()
+                        .unwrap_or_default();
                     tier_args.push(
                         (
                             managed_buffer!(tier.clone()),
                             *nr_nfts,
                             managed_biguint!(mint_price_amount),
+                            tier_max_nfts_per_wallet,
+                            managed_extra_price_options.clone(),
                         )
                             .into(),
                     );
                 }
 
+                let (file_uri_template, metadata_template) =
+                    opt_uri_templates.unwrap_or((b"", b""));
+
                 sc.issue_token_for_brand(
-                    collection_hash.into(),
+                    managed_buffer!(collection_hash),
                     managed_buffer!(brand_id),
                     managed_buffer!(media_type),
                     managed_biguint!(royalties),
@@ -236,6 +307,63 @@ where
                     managed_buffer!(token_display_name),
                     managed_buffer!(token_ticker),
                     whitelist_expire_epoch,
+                    max_nfts_per_wallet,
+                    delayed_reveal,
+                    managed_buffer!(file_uri_template),
+                    managed_buffer!(metadata_template),
+                    managed_tags,
+                    tier_args,
+                );
+            },
+        )
+    }
+
+    /// Registers a brand with the custom brand's settings, using an already issued token
+    pub fn call_register_brand_with_existing_token(
+        &mut self,
+        nft_token_id: &[u8],
+        collection_hash: &[u8],
+        brand_id: &[u8],
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut managed_tags = ManagedVec::new();
+                for tag in CUSTOM_TAGS {
+                    managed_tags.push(managed_buffer!(tag));
+                }
+
+                let mut tier_args = MultiValueEncoded::new();
+                for (tier, nr_nfts) in CUSTOM_TIERS.iter().zip(CUSTOM_NFT_AMOUNTS.iter()) {
+                    tier_args.push(
+                        (
+                            managed_buffer!(tier),
+                            *nr_nfts,
+                            managed_biguint!(CUSTOM_MINT_PRICE_AMOUNT),
+                            0,
+                            ManagedVec::new(),
+                        )
+                            .into(),
+                    );
+                }
+
+                sc.register_brand_with_existing_token(
+                    TokenIdentifier::from_esdt_bytes(nft_token_id),
+                    managed_buffer!(collection_hash),
+                    managed_buffer!(brand_id),
+                    managed_buffer!(CUSTOM_MEDIA_TYPE),
+                    managed_biguint!(CUSTOM_ROYALTIES),
+                    CUSTOM_MINT_START_TIMESTAMP,
+                    CUSTOM_MINT_END_TIMESTAMP,
+                    managed_token_id!(CUSTOM_MINT_PRICE_TOKEN_ID),
+                    managed_buffer!(CUSTOM_TOKEN_DISPLAY_NAME),
+                    0,
+                    0,
+                    false,
+                    ManagedBuffer::new(),
+                    ManagedBuffer::new(),
                     managed_tags,
                     tier_args,
                 );
@@ -268,6 +396,7 @@ where
                         managed_buffer!(brand_id),
                         managed_buffer!(tier),
                         opt_nft_amount,
+                        OptionalValue::None,
                     );
                 },
             )
@@ -283,12 +412,341 @@ where
                         managed_buffer!(brand_id),
                         managed_buffer!(tier),
                         opt_nft_amount,
+                        OptionalValue::None,
                     );
                 },
             )
         }
     }
 
+    pub fn call_buy_random_nft_for(
+        &mut self,
+        buyer_address: &Address,
+        recipient_address: &Address,
+        payment_amount: u64,
+        brand_id: &[u8],
+        tier: &[u8],
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            buyer_address,
+            &self.nm_wrapper,
+            &rust_biguint!(payment_amount),
+            |sc| {
+                let _ = sc.buy_random_nft_for(
+                    managed_buffer!(brand_id),
+                    managed_buffer!(tier),
+                    managed_address!(recipient_address),
+                    OptionalValue::None,
+                    OptionalValue::None,
+                );
+            },
+        )
+    }
+
+    /// Sends the gate token (token, nonce) along with the (token, amount) payment
+    pub fn call_buy_random_nft_with_gate(
+        &mut self,
+        buyer_address: &Address,
+        gate_token: (&[u8], u64),
+        payment: (&[u8], u64),
+        brand_id: &[u8],
+        tier: &[u8],
+    ) -> TxResult {
+        let transfers = [
+            TxInputESDT {
+                token_identifier: gate_token.0.to_vec(),
+                nonce: gate_token.1,
+                value: rust_biguint!(1),
+            },
+            TxInputESDT {
+                token_identifier: payment.0.to_vec(),
+                nonce: 0,
+                value: rust_biguint!(payment.1),
+            },
+        ];
+
+        self.b_mock
+            .execute_esdt_multi_transfer(buyer_address, &self.nm_wrapper, &transfers, |sc| {
+                let _ = sc.buy_random_nft(
+                    managed_buffer!(brand_id),
+                    managed_buffer!(tier),
+                    OptionalValue::None,
+                    OptionalValue::None,
+                );
+            })
+    }
+
+    /// Pays for each (tier, NFTs) pair with the (token, amount) payment at the same position
+    pub fn call_buy_random_nfts_multi_tier(
+        &mut self,
+        buyer_address: &Address,
+        brand_id: &[u8],
+        tier_nfts_pairs: &[(&[u8], usize)],
+        payments: &[(&[u8], u64)],
+    ) -> TxResult {
+        let transfers: Vec<TxInputESDT> = payments
+            .iter()
+            .map(|(token_id, amount)| TxInputESDT {
+                token_identifier: token_id.to_vec(),
+                nonce: 0,
+                value: rust_biguint!(*amount),
+            })
+            .collect();
+
+        self.b_mock
+            .execute_esdt_multi_transfer(buyer_address, &self.nm_wrapper, &transfers, |sc| {
+                let mut args = MultiValueEncoded::new();
+                for (tier, nfts_to_buy) in tier_nfts_pairs {
+                    args.push((managed_buffer!(tier), *nfts_to_buy).into());
+                }
+
+                let _ = sc.buy_random_nfts_multi_tier(managed_buffer!(brand_id), args);
+            })
+    }
+
+    /// Sends the (token, nonce, amount) transfers to be redeemed
+    pub fn call_redeem_nfts(
+        &mut self,
+        user_address: &Address,
+        brand_id: &[u8],
+        source_nfts: &[(&[u8], u64, u64)],
+    ) -> TxResult {
+        let transfers: Vec<TxInputESDT> = source_nfts
+            .iter()
+            .map(|(token_id, nonce, amount)| TxInputESDT {
+                token_identifier: token_id.to_vec(),
+                nonce: *nonce,
+                value: rust_biguint!(*amount),
+            })
+            .collect();
+
+        self.b_mock
+            .execute_esdt_multi_transfer(user_address, &self.nm_wrapper, &transfers, |sc| {
+                let _ = sc.redeem_nfts(managed_buffer!(brand_id));
+            })
+    }
+
+    pub fn call_fuse_nfts(
+        &mut self,
+        user_address: &Address,
+        brand_id: &[u8],
+        nft_token_id: &[u8],
+        nft_nonces: &[u64],
+    ) -> TxResult {
+        let transfers: Vec<TxInputESDT> = nft_nonces
+            .iter()
+            .map(|nonce| TxInputESDT {
+                token_identifier: nft_token_id.to_vec(),
+                nonce: *nonce,
+                value: rust_biguint!(1),
+            })
+            .collect();
+
+        self.b_mock
+            .execute_esdt_multi_transfer(user_address, &self.nm_wrapper, &transfers, |sc| {
+                let _ = sc.fuse_nfts(managed_buffer!(brand_id));
+            })
+    }
+
+    pub fn call_buy_random_nft_with_proof(
+        &mut self,
+        buyer_address: &Address,
+        payment_amount: u64,
+        brand_id: &[u8],
+        tier: &[u8],
+        nfts_to_buy: usize,
+        whitelist_proof: WhitelistProof,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            buyer_address,
+            &self.nm_wrapper,
+            &rust_biguint!(payment_amount),
+            |sc| {
+                let mut managed_proof = ManagedVec::new();
+                for hash in whitelist_proof.proof {
+                    managed_proof.push(ManagedByteArray::new_from_bytes(hash));
+                }
+
+                sc.buy_random_nft(
+                    managed_buffer!(brand_id),
+                    managed_buffer!(tier),
+                    OptionalValue::Some(nfts_to_buy),
+                    OptionalValue::Some((whitelist_proof.allowance, managed_proof).into()),
+                );
+            },
+        )
+    }
+
+    pub fn sign_mint_voucher(
+        &self,
+        signer: &Keypair,
+        buyer_address: &Address,
+        voucher: &MintVoucherArgs,
+    ) -> [u8; 64] {
+        let mut message = Vec::new();
+        message.extend_from_slice(self.nm_wrapper.address_ref().as_bytes());
+        message.extend_from_slice(buyer_address.as_bytes());
+        message.extend_from_slice(&(voucher.brand_id.len() as u32).to_be_bytes());
+        message.extend_from_slice(voucher.brand_id);
+        message.extend_from_slice(&(voucher.tier.len() as u32).to_be_bytes());
+        message.extend_from_slice(voucher.tier);
+        message.extend_from_slice(&(voucher.nfts_to_buy as u32).to_be_bytes());
+        message.extend_from_slice(&voucher.nonce.to_be_bytes());
+        message.extend_from_slice(&voucher.expire_timestamp.to_be_bytes());
+
+        signer.sign(&message).to_bytes()
+    }
+
+    pub fn call_buy_random_nft_with_voucher(
+        &mut self,
+        buyer_address: &Address,
+        payment_amount: u64,
+        voucher: &MintVoucherArgs,
+        signature: &[u8; 64],
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            buyer_address,
+            &self.nm_wrapper,
+            &rust_biguint!(payment_amount),
+            |sc| {
+                sc.buy_random_nft_with_voucher(
+                    managed_buffer!(voucher.brand_id),
+                    managed_buffer!(voucher.tier),
+                    voucher.nfts_to_buy,
+                    voucher.nonce,
+                    voucher.expire_timestamp,
+                    ManagedByteArray::new_from_bytes(signature),
+                );
+            },
+        )
+    }
+
+    pub fn call_reveal_brand(&mut self, brand_id: &[u8], collection_hash: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.reveal_brand(managed_buffer!(brand_id), collection_hash.into());
+            },
+        )
+    }
+
+    pub fn call_reveal_nfts(
+        &mut self,
+        holder_address: &Address,
+        brand_id: &[u8],
+        nft_token_id: &[u8],
+        nft_nonces: &[u64],
+    ) -> TxResult {
+        let transfers: Vec<TxInputESDT> = nft_nonces
+            .iter()
+            .map(|nonce| TxInputESDT {
+                token_identifier: nft_token_id.to_vec(),
+                nonce: *nonce,
+                value: rust_biguint!(1),
+            })
+            .collect();
+
+        self.b_mock.execute_esdt_multi_transfer(
+            holder_address,
+            &self.nm_wrapper,
+            &transfers,
+            |sc| {
+                let _ = sc.reveal_nfts(managed_buffer!(brand_id));
+            },
+        )
+    }
+
+    /// Leaves the SC in the state `issueTokenForBrand` leaves it in while the issue call is pending
+    pub fn set_pending_brand_creation(
+        &mut self,
+        brand_id: &[u8],
+        collection_hash: &[u8],
+        original_caller: &Address,
+        issue_id: u64,
+    ) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.nm_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    let _ = sc.registered_brands().insert(managed_buffer!(brand_id));
+                    let _ = sc
+                        .registered_collection_hashes()
+                        .insert(managed_buffer!(collection_hash));
+                    let _ = sc
+                        .nft_tiers_for_brand(&managed_buffer!(brand_id))
+                        .insert(managed_buffer!(FIRST_TIERS[0]));
+
+                    sc.temporary_callback_storage(&managed_buffer!(brand_id))
+                        .set(&TempCallbackStorageInfo {
+                            original_caller: managed_address!(original_caller),
+                            brand_info: BrandInfo {
+                                collection_hash: managed_buffer!(collection_hash),
+                                token_display_name: managed_buffer!(FIRST_TOKEN_DISPLAY_NAME),
+                                media_type: managed_buffer!(FIRST_MEDIA_TYPE),
+                                royalties: managed_biguint!(0),
+                                mint_period: TimePeriod {
+                                    start: FIRST_MINT_START_TIMESTAMP,
+                                    end: FIRST_MINT_END_TIMESTAMP,
+                                },
+                                whitelist_expire_timestamp: 0,
+                                is_revealed: true,
+                            },
+                            max_nfts_per_wallet: 0,
+                            opt_uri_templates: None,
+                            tags: ManagedVec::new(),
+                            tier_info_entries: ArrayVec::new(),
+                        });
+                    sc.pending_issue_id(&managed_buffer!(brand_id))
+                        .set(issue_id);
+                },
+            )
+            .assert_ok();
+    }
+
+    /// The issue cost is sent back along with a failed issue callback
+    pub fn call_issue_callback(
+        &mut self,
+        brand_id: &[u8],
+        collection_hash: &[u8],
+        original_caller: &Address,
+        issue_id: u64,
+        result: Result<&[u8], &[u8]>,
+    ) -> TxResult {
+        let returned_amount = match result {
+            Ok(_) => 0,
+            Err(_) => ISSUE_COST,
+        };
+
+        self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.nm_wrapper,
+            &rust_biguint!(returned_amount),
+            |sc| {
+                let call_result = match result {
+                    Ok(token_id) => {
+                        ManagedAsyncCallResult::Ok(TokenIdentifier::from_esdt_bytes(token_id))
+                    }
+                    Err(err_msg) => ManagedAsyncCallResult::Err(ManagedAsyncCallError {
+                        err_code: 1,
+                        err_msg: managed_buffer!(err_msg),
+                    }),
+                };
+
+                sc.issue_callback(
+                    managed_buffer!(collection_hash),
+                    managed_buffer!(brand_id),
+                    managed_address!(original_caller),
+                    issue_id,
+                    call_result,
+                );
+            },
+        )
+    }
+
     pub fn call_giveaway(
         &mut self,
         brand_id: &[u8],
@@ -309,4 +767,49 @@ where
             },
         )
     }
+
+    pub fn call_add_mint_phase(&mut self, brand_id: &[u8], phase: MintPhaseArgs) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut args = MultiValueEncoded::new();
+                for (tier, price) in phase.tier_prices {
+                    args.push((managed_buffer!(tier), managed_biguint!(*price)).into());
+                }
+
+                sc.add_mint_phase(
+                    managed_buffer!(brand_id),
+                    managed_buffer!(phase.name),
+                    phase.start_timestamp,
+                    phase.end_timestamp,
+                    phase.whitelist_only,
+                    phase.max_nfts_per_wallet,
+                    args,
+                );
+            },
+        )
+    }
+
+    pub fn call_set_max_nfts_per_wallet(
+        &mut self,
+        brand_id: &[u8],
+        opt_tier: Option<&[u8]>,
+        max: usize,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| match opt_tier {
+                Some(tier) => sc.set_max_nfts_per_wallet_for_tier(
+                    managed_buffer!(brand_id),
+                    managed_buffer!(tier),
+                    max,
+                ),
+                None => sc.set_max_nfts_per_wallet(managed_buffer!(brand_id), max),
+            },
+        )
+    }
 }
@@ -2,18 +2,38 @@ pub mod constants;
 pub mod nft_minter_interactor;
 
 use constants::*;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use elrond_wasm::contract_base::ContractBase;
+use elrond_wasm::elrond_codec::multi_types::OptionalValue;
+use elrond_wasm::elrond_codec::{TopDecode, TopEncode};
 use elrond_wasm::storage::mappers::StorageTokenWrapper;
-use elrond_wasm::types::{ManagedBuffer, ManagedByteArray, MultiValueEncoded, ManagedVec};
+use elrond_wasm::types::{
+    Address, EsdtLocalRole, ManagedBuffer, ManagedByteArray, ManagedVec, MultiValueEncoded,
+    OperationCompletionStatus, TokenIdentifier,
+};
 use elrond_wasm_debug::testing_framework::BlockchainStateWrapper;
 use elrond_wasm_debug::{managed_address, managed_biguint, managed_buffer, rust_biguint, DebugApi};
+use merkle_whitelist_builder::{MerkleTree, WhitelistEntry};
 use nft_minter::brand_creation::BrandCreationModule;
-use nft_minter::common_storage::{BrandInfo, MintPrice, TimePeriod, CommonStorageModule};
-use nft_minter::nft_attributes_builder::{NftAttributesBuilderModule, COLLECTION_HASH_LEN};
+use nft_minter::common_storage::{BrandInfo, CommonStorageModule, MintPrice, TimePeriod};
+use nft_minter::merkle_whitelist::MerkleWhitelistModule;
+use nft_minter::mint_phases::MintPhasesModule;
+use nft_minter::mint_vouchers::MintVouchersModule;
+use nft_minter::mystery_tier::MysteryTierModule;
+use nft_minter::nft_attributes_builder::NftAttributesBuilderModule;
+use nft_minter::nft_fusion::NftFusionModule;
+use nft_minter::nft_minting::NftMintingModule;
+use nft_minter::nft_redemption::NftRedemptionModule;
+use nft_minter::nft_reveal::NftRevealModule;
 use nft_minter::nft_tier::NftTierModule;
+use nft_minter::pause::PauseModule;
+use nft_minter::pricing::{BondingCurve, CurveKind, DutchAuction, PricingModule, PricingStrategy};
 use nft_minter::royalties::RoyaltiesModule;
 use nft_minter::views::{TierInfoEntry, ViewsModule};
+use nft_minter::wallet_limits::{GiftLimitsParty, WalletLimitsModule};
 use nft_minter::NftMinter;
 use nft_minter_interactor::*;
+use std::convert::TryInto;
 
 #[test]
 fn init_test() {
@@ -42,6 +62,11 @@ fn create_brands_test() {
             FIRST_TIERS,
             FIRST_NFT_AMOUNTS,
             0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
         )
         .assert_user_error("Collection hash already exists");
 
@@ -62,6 +87,11 @@ fn create_brands_test() {
             FIRST_TIERS,
             FIRST_NFT_AMOUNTS,
             0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
         )
         .assert_user_error("Brand already exists");
 
@@ -82,6 +112,11 @@ fn create_brands_test() {
             FIRST_TIERS,
             FIRST_NFT_AMOUNTS,
             0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
         )
         .assert_user_error("Invalid media type");
 
@@ -98,9 +133,7 @@ fn create_brands_test() {
             assert_eq!(result.nft_token_id, expected_token_id.unwrap_esdt());
 
             let expected_brand_info = BrandInfo::<DebugApi> {
-                collection_hash: ManagedByteArray::<DebugApi, COLLECTION_HASH_LEN>::new_from_bytes(
-                    FIRST_COLLECTION_HASH,
-                ),
+                collection_hash: managed_buffer!(FIRST_COLLECTION_HASH),
                 token_display_name: managed_buffer!(FIRST_TOKEN_DISPLAY_NAME),
                 media_type: managed_buffer!(FIRST_MEDIA_TYPE),
                 royalties: managed_biguint!(0),
@@ -109,6 +142,7 @@ fn create_brands_test() {
                     end: FIRST_MINT_END_TIMESTAMP,
                 },
                 whitelist_expire_timestamp: 0,
+                is_revealed: true,
             };
             assert_eq!(result.brand_info, expected_brand_info);
 
@@ -122,6 +156,11 @@ fn create_brands_test() {
                         token_id: managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
                         amount: managed_biguint!(FIRST_MINT_PRICE_AMOUNT),
                     },
+                    price_options: ManagedVec::from_single_item(MintPrice::<DebugApi> {
+                        token_id: managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                        amount: managed_biguint!(FIRST_MINT_PRICE_AMOUNT),
+                    }),
+                    is_paused: false,
                 });
             }
             assert_eq!(
@@ -459,7 +498,7 @@ fn buy_whitelist_test() {
             &rust_biguint!(0),
             |sc| {
                 let mut args = MultiValueEncoded::new();
-                args.push(managed_address!(&first_user_addr));
+                args.push((managed_address!(&first_user_addr), 0).into());
                 sc.add_to_whitelist(managed_buffer!(FIRST_BRAND_ID), args);
             },
         )
@@ -480,236 +519,4926 @@ fn buy_whitelist_test() {
 }
 
 #[test]
-fn giveaway_test() {
+fn mint_phases_test() {
     let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
     nm_setup.create_default_brands();
 
-    let first_tier = SECOND_TIERS[0];
+    let first_tier = FIRST_TIERS[0];
+    let presale_end = FIRST_MINT_START_TIMESTAMP + 1_000;
+    let public_start = FIRST_MINT_START_TIMESTAMP + 2_000;
+    let presale_price = FIRST_MINT_PRICE_AMOUNT / 2;
 
-    // giveaway single nft
-    let first_user_addr = nm_setup.first_user_address.clone();
     nm_setup
-        .call_giveaway(
-            SECOND_BRAND_ID,
-            first_tier,
-            [(first_user_addr.clone(), 1)].to_vec(),
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"presale",
+                start_timestamp: FIRST_MINT_START_TIMESTAMP,
+                end_timestamp: presale_end,
+                whitelist_only: true,
+                max_nfts_per_wallet: 1,
+                tier_prices: &[(first_tier, presale_price)],
+            },
         )
         .assert_ok();
 
-    // user received nonce 1 and ID 7
-    let mut attr = nm_setup.build_nft_attributes_second_token(7);
-    nm_setup.b_mock.check_nft_balance(
-        &first_user_addr,
-        SECOND_TOKEN_ID,
-        1,
-        &rust_biguint!(1),
-        Some(&attr),
-    );
-
+    // try add overlapping phase
     nm_setup
-        .b_mock
-        .execute_query(&nm_setup.nm_wrapper, |sc| {
-            let mapper = sc.available_ids(
-                &managed_buffer!(SECOND_BRAND_ID),
-                &managed_buffer!(first_tier),
-            );
-            assert_eq!(mapper.len(), 9);
-            assert_eq!(mapper.get(1), 1);
-            assert_eq!(mapper.get(2), 2);
-            assert_eq!(mapper.get(3), 3);
-            assert_eq!(mapper.get(4), 4);
-            assert_eq!(mapper.get(5), 5);
-            assert_eq!(mapper.get(6), 6);
-            assert_eq!(mapper.get(7), 10); // this changed
-            assert_eq!(mapper.get(8), 8);
-            assert_eq!(mapper.get(9), 9);
-        })
-        .assert_ok();
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"public",
+                start_timestamp: presale_end - 1,
+                end_timestamp: FIRST_MINT_END_TIMESTAMP,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[],
+            },
+        )
+        .assert_user_error("Phases must be in order and may not overlap");
 
-    // giveaway two, single user
     nm_setup
-        .call_giveaway(
-            SECOND_BRAND_ID,
-            first_tier,
-            [(first_user_addr.clone(), 2)].to_vec(),
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"public",
+                start_timestamp: public_start,
+                end_timestamp: FIRST_MINT_END_TIMESTAMP,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[],
+            },
         )
         .assert_ok();
 
-    // received ID 4 and 5
-    attr = nm_setup.build_nft_attributes_second_token(4);
-    nm_setup.b_mock.check_nft_balance(
-        &first_user_addr,
-        SECOND_TOKEN_ID,
-        2,
-        &rust_biguint!(1),
-        Some(&attr),
-    );
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
 
-    attr = nm_setup.build_nft_attributes_second_token(5);
-    nm_setup.b_mock.check_nft_balance(
-        &first_user_addr,
-        SECOND_TOKEN_ID,
-        3,
-        &rust_biguint!(1),
-        Some(&attr),
-    );
+    // try buy in presale, not in whitelist
+    let first_user_addr = nm_setup.first_user_address.clone();
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            presale_price,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Not in whitelist");
 
     nm_setup
         .b_mock
-        .execute_query(&nm_setup.nm_wrapper, |sc| {
-            let mapper = sc.available_ids(
-                &managed_buffer!(SECOND_BRAND_ID),
-                &managed_buffer!(first_tier),
-            );
-            assert_eq!(mapper.len(), 7);
-            assert_eq!(mapper.get(1), 1);
-            assert_eq!(mapper.get(2), 2);
-            assert_eq!(mapper.get(3), 3);
-            assert_eq!(mapper.get(4), 9); // pos 4
-            assert_eq!(mapper.get(5), 8); // and 5 changed
-            assert_eq!(mapper.get(6), 6);
-            assert_eq!(mapper.get(7), 10);
-        })
+        .execute_tx(
+            &nm_setup.owner_address.clone(),
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut args = MultiValueEncoded::new();
+                args.push((managed_address!(&first_user_addr), 0).into());
+                sc.add_to_whitelist(managed_buffer!(FIRST_BRAND_ID), args);
+            },
+        )
         .assert_ok();
 
-    // giveaway, multiple users
-    let second_user_addr = nm_setup.second_user_address.clone();
+    // try buy in presale with less than the presale price
     nm_setup
-        .call_giveaway(
-            SECOND_BRAND_ID,
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            presale_price - 1,
+            FIRST_BRAND_ID,
             first_tier,
-            [(first_user_addr.clone(), 2), (second_user_addr.clone(), 1)].to_vec(),
+            1,
         )
-        .assert_ok();
-
-    // user 1 received IDs 10 and 1
-    attr = nm_setup.build_nft_attributes_second_token(10);
-    nm_setup.b_mock.check_nft_balance(
-        &first_user_addr,
-        SECOND_TOKEN_ID,
-        4,
-        &rust_biguint!(1),
-        Some(&attr),
-    );
+        .assert_user_error("Invalid payment");
 
-    attr = nm_setup.build_nft_attributes_second_token(1);
-    nm_setup.b_mock.check_nft_balance(
-        &first_user_addr,
-        SECOND_TOKEN_ID,
-        5,
-        &rust_biguint!(1),
-        Some(&attr),
-    );
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            presale_price,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_ok();
 
-    // user 2 received ID
-    attr = nm_setup.build_nft_attributes_second_token(6);
-    nm_setup.b_mock.check_nft_balance(
-        &second_user_addr,
-        SECOND_TOKEN_ID,
-        6,
-        &rust_biguint!(1),
-        Some(&attr),
-    );
+    // try buy over the presale wallet limit
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            presale_price,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Phase wallet limit exceeded");
 
-    // mapper progress:
-    // 1 2 3 9 8 6 10
-    // 1 2 3 9 8 6
-    // 6 2 3 9 8
-    // 8 2 3 9
     nm_setup
         .b_mock
         .execute_query(&nm_setup.nm_wrapper, |sc| {
-            let mapper = sc.available_ids(
-                &managed_buffer!(SECOND_BRAND_ID),
-                &managed_buffer!(first_tier),
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            let active_phase = result.active_phase.unwrap();
+            assert_eq!(active_phase.name, managed_buffer!(b"presale"));
+
+            let gold_tier_info = result.tier_info_entries.first().unwrap();
+            assert_eq!(
+                gold_tier_info.mint_price.amount,
+                managed_biguint!(presale_price)
             );
-            assert_eq!(mapper.len(), 4);
-            assert_eq!(mapper.get(1), 8);
-            assert_eq!(mapper.get(2), 2);
-            assert_eq!(mapper.get(3), 3);
-            assert_eq!(mapper.get(4), 9);
         })
         .assert_ok();
-}
 
-#[test]
-fn formatters_test() {
-    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
-    nm_setup.create_default_brands();
+    // try buy between phases
+    nm_setup.b_mock.set_block_timestamp(presale_end);
 
-    // test NFT attributes
+    let second_user_addr = nm_setup.second_user_address.clone();
     nm_setup
-        .b_mock
-        .execute_query(&nm_setup.nm_wrapper, |sc| {
-            let attributes = sc.build_nft_attributes(
-                &ManagedByteArray::new_from_bytes(FIRST_COLLECTION_HASH),
-                &managed_buffer!(FIRST_BRAND_ID),
-                2,
-            );
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("No active mint phase");
 
-            let expected_attributes =
-                "metadata:FirstCollection_______________________________/2.json;tags:funny,sad,memes";
-            assert_eq!(managed_buffer_to_string(&attributes), expected_attributes.to_string());
-        })
+    // public phase, base price and no whitelist
+    nm_setup.b_mock.set_block_timestamp(public_start);
+
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
         .assert_ok();
 
-    // test generated URIs
     nm_setup
         .b_mock
         .execute_query(&nm_setup.nm_wrapper, |sc| {
-            let coll_hash = ManagedByteArray::new_from_bytes(FIRST_COLLECTION_HASH);
-            let nft_main_file_uri =
-                sc.build_nft_main_file_uri(&coll_hash, 2, &managed_buffer!(b"jpg"));
-            let expected_main_file_uri =
-                "https://ipfs.io/ipfs/FirstCollection_______________________________/2.jpg";
-            assert_eq!(
-                managed_buffer_to_string(&nft_main_file_uri),
-                expected_main_file_uri.to_string()
-            );
-
-            let nft_json_file_uri = sc.build_nft_json_file_uri(&coll_hash, 2);
-            let expected_nft_json_uri =
-                "https://ipfs.io/ipfs/FirstCollection_______________________________/2.json";
-            assert_eq!(
-                managed_buffer_to_string(&nft_json_file_uri),
-                expected_nft_json_uri.to_string()
-            );
-
-            let collection_json_uri = sc.build_collection_json_file_uri(&coll_hash);
-            let expected_collection_json_uri = "https://ipfs.io/ipfs/FirstCollection_______________________________/collection.json";
-            assert_eq!(
-                managed_buffer_to_string(&collection_json_uri),
-                expected_collection_json_uri.to_string()
-            );
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            let active_phase = result.active_phase.unwrap();
+            assert_eq!(active_phase.name, managed_buffer!(b"public"));
         })
         .assert_ok();
-}
 
-#[test]
-fn custom_test() {
-    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
-    let mut b_mock = BlockchainStateWrapper::new();
-    let owner_address = b_mock.create_user_account(&rust_biguint!(OWNER_EGLD_BALANCE));
-    
-    // check contract storage after init()
+    // phases may only change during the mint if the brand is paused
+    let owner_addr = nm_setup.owner_address.clone();
     nm_setup
         .b_mock
-        .execute_query(&nm_setup.nm_wrapper, |sc| {
-            let mapper = sc.collections_category();
-            assert_eq!(mapper.get(), managed_buffer!(CATEGORY));
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.clear_mint_phases(managed_buffer!(FIRST_BRAND_ID));
         })
-        .assert_ok();
+        .assert_user_error("May not change phases during mint unless paused");
 
     nm_setup
         .b_mock
-        .execute_query(&nm_setup.nm_wrapper, |sc| {
-            let mapper = sc.royalties_claim_address();
-            assert_eq!(mapper.get(), managed_address!(&owner_address));
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.pause_brand(managed_buffer!(FIRST_BRAND_ID));
+            sc.clear_mint_phases(managed_buffer!(FIRST_BRAND_ID));
         })
         .assert_ok();
 
+    // names are not reused, so the NFTs minted in the old phase don't count for the new one
     nm_setup
-        .b_mock
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"presale",
+                start_timestamp: public_start,
+                end_timestamp: FIRST_MINT_END_TIMESTAMP,
+                whitelist_only: true,
+                max_nfts_per_wallet: 1,
+                tier_prices: &[],
+            },
+        )
+        .assert_user_error("Phase name already used");
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"second presale",
+                start_timestamp: public_start,
+                end_timestamp: FIRST_MINT_END_TIMESTAMP,
+                whitelist_only: true,
+                max_nfts_per_wallet: 1,
+                tier_prices: &[],
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn wallet_limits_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    nm_setup.create_default_brands();
+
+    // limits given at brand creation
+    nm_setup
+        .call_create_new_brand(
+            THIRD_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+            b"png",
+            0,
+            1,
+            2,
+            b"EGLD",
+            1,
+            b"",
+            b"TICKER",
+            &[],
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            5,
+            &[1],
+            false,
+            None,
+            &[],
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(THIRD_BRAND_ID);
+            assert_eq!(sc.max_nfts_per_wallet(&brand_id).get(), 5);
+            assert_eq!(
+                sc.max_nfts_per_wallet_for_tier(&brand_id, &managed_buffer!(FIRST_TIERS[0]))
+                    .get(),
+                1
+            );
+            assert_eq!(
+                sc.max_nfts_per_wallet_for_tier(&brand_id, &managed_buffer!(FIRST_TIERS[1]))
+                    .get(),
+                0
+            );
+        })
+        .assert_ok();
+
+    // limits edited after creation
+    let first_tier = FIRST_TIERS[0];
+    let second_tier = FIRST_TIERS[1];
+    nm_setup
+        .call_set_max_nfts_per_wallet(FIRST_BRAND_ID, None, 3)
+        .assert_ok();
+    nm_setup
+        .call_set_max_nfts_per_wallet(FIRST_BRAND_ID, Some(first_tier), 2)
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    let first_user_addr = nm_setup.first_user_address.clone();
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT * 2,
+            FIRST_BRAND_ID,
+            first_tier,
+            2,
+        )
+        .assert_ok();
+
+    // try buy over tier limit
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Wallet limit exceeded for tier");
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            second_tier,
+            1,
+        )
+        .assert_ok();
+
+    // try buy over brand limit
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            second_tier,
+            1,
+        )
+        .assert_user_error("Wallet limit exceeded for brand");
+
+    // other users are not affected
+    let second_user_addr = nm_setup.second_user_address.clone();
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            second_tier,
+            1,
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(FIRST_BRAND_ID);
+            assert_eq!(
+                sc.minted_count_for_address(&brand_id, &managed_address!(&first_user_addr))
+                    .get(),
+                3
+            );
+            assert_eq!(
+                sc.minted_count_for_address_in_tier(
+                    &brand_id,
+                    &managed_buffer!(first_tier),
+                    &managed_address!(&first_user_addr)
+                )
+                .get(),
+                2
+            );
+            assert_eq!(
+                sc.minted_count_for_address(&brand_id, &managed_address!(&second_user_addr))
+                    .get(),
+                1
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn merkle_whitelist_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_tier = FIRST_TIERS[0];
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let second_user_addr = nm_setup.second_user_address.clone();
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    let mut entries = vec![
+        WhitelistEntry {
+            address: *first_user_addr.as_array(),
+            allowance: 1,
+        },
+        WhitelistEntry {
+            address: *second_user_addr.as_array(),
+            allowance: 0,
+        },
+    ];
+    for i in 0..3u8 {
+        entries.push(WhitelistEntry {
+            address: [i; 32],
+            allowance: 5,
+        });
+    }
+    let tree = MerkleTree::from_entries(&entries);
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &nm_setup.owner_address.clone(),
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_mint_whitelist_expire_timestamp(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    FIRST_MINT_END_TIMESTAMP,
+                );
+                sc.set_whitelist_merkle_root(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    ManagedByteArray::new_from_bytes(&tree.root()),
+                );
+            },
+        )
+        .assert_ok();
+
+    // try buy without proof
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Whitelist proof required");
+
+    // try buy with wrong allowance
+    nm_setup
+        .call_buy_random_nft_with_proof(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+            WhitelistProof {
+                allowance: 2,
+                proof: &tree.proof(0),
+            },
+        )
+        .assert_user_error("Invalid whitelist proof");
+
+    // try buy with another user's proof
+    nm_setup
+        .call_buy_random_nft_with_proof(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+            WhitelistProof {
+                allowance: 0,
+                proof: &tree.proof(1),
+            },
+        )
+        .assert_user_error("Invalid whitelist proof");
+
+    nm_setup
+        .call_buy_random_nft_with_proof(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+            WhitelistProof {
+                allowance: 1,
+                proof: &tree.proof(0),
+            },
+        )
+        .assert_ok();
+
+    // try buy over allowance
+    nm_setup
+        .call_buy_random_nft_with_proof(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+            WhitelistProof {
+                allowance: 1,
+                proof: &tree.proof(0),
+            },
+        )
+        .assert_user_error("Whitelist allowance exceeded");
+
+    // no allowance limit for second user
+    nm_setup
+        .call_buy_random_nft_with_proof(
+            &second_user_addr,
+            FIRST_MINT_PRICE_AMOUNT * 2,
+            FIRST_BRAND_ID,
+            first_tier,
+            2,
+            WhitelistProof {
+                allowance: 0,
+                proof: &tree.proof(1),
+            },
+        )
+        .assert_ok();
+    nm_setup
+        .call_buy_random_nft_with_proof(
+            &second_user_addr,
+            FIRST_MINT_PRICE_AMOUNT * 2,
+            FIRST_BRAND_ID,
+            first_tier,
+            2,
+            WhitelistProof {
+                allowance: 0,
+                proof: &tree.proof(1),
+            },
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let minted = sc
+                .merkle_whitelist_minted(
+                    &managed_buffer!(FIRST_BRAND_ID),
+                    &managed_address!(&second_user_addr),
+                )
+                .get();
+            assert_eq!(minted, 4);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn mint_voucher_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_tier = FIRST_TIERS[0];
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let second_user_addr = nm_setup.second_user_address.clone();
+
+    let secret = SecretKey::from_bytes(&[1u8; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    let signer = Keypair { secret, public };
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    // whitelist is required, but vouchers replace it
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &nm_setup.owner_address.clone(),
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_mint_whitelist_expire_timestamp(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    FIRST_MINT_END_TIMESTAMP,
+                );
+                sc.set_mint_voucher_signer(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    ManagedByteArray::new_from_bytes(public.as_bytes()),
+                );
+            },
+        )
+        .assert_ok();
+
+    // try buy without voucher
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Brand requires a mint voucher");
+
+    let expire_timestamp = FIRST_MINT_START_TIMESTAMP + 100;
+    let voucher = MintVoucherArgs {
+        brand_id: FIRST_BRAND_ID,
+        tier: first_tier,
+        nfts_to_buy: 1,
+        nonce: 1,
+        expire_timestamp,
+    };
+    let signature = nm_setup.sign_mint_voucher(&signer, &first_user_addr, &voucher);
+
+    // try use another user's voucher
+    nm_setup
+        .call_buy_random_nft_with_voucher(
+            &second_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            &voucher,
+            &signature,
+        )
+        .assert_user_error("Invalid mint voucher signature");
+
+    // try use voucher for more NFTs
+    nm_setup
+        .call_buy_random_nft_with_voucher(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT * 2,
+            &MintVoucherArgs {
+                nfts_to_buy: 2,
+                ..voucher
+            },
+            &signature,
+        )
+        .assert_user_error("Invalid mint voucher signature");
+
+    nm_setup
+        .call_buy_random_nft_with_voucher(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            &voucher,
+            &signature,
+        )
+        .assert_ok();
+
+    // try replay voucher
+    nm_setup
+        .call_buy_random_nft_with_voucher(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            &voucher,
+            &signature,
+        )
+        .assert_user_error("Mint voucher already used");
+
+    // try use expired voucher
+    let expired_voucher = MintVoucherArgs {
+        nonce: 2,
+        expire_timestamp: FIRST_MINT_START_TIMESTAMP,
+        ..voucher
+    };
+    let expired_signature = nm_setup.sign_mint_voucher(&signer, &first_user_addr, &expired_voucher);
+    nm_setup
+        .call_buy_random_nft_with_voucher(
+            &first_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            &expired_voucher,
+            &expired_signature,
+        )
+        .assert_user_error("Mint voucher expired");
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(FIRST_BRAND_ID);
+            assert!(sc.mint_voucher_used(&brand_id, 1).get());
+            assert!(!sc.mint_voucher_used(&brand_id, 2).get());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn delayed_reveal_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_tier = FIRST_TIERS[0];
+    let first_user_addr = nm_setup.first_user_address.clone();
+
+    // FIRST_COLLECTION_HASH is used as placeholder
+    nm_setup
+        .call_create_new_brand(
+            FIRST_COLLECTION_HASH,
+            FIRST_BRAND_ID,
+            FIRST_MEDIA_TYPE,
+            0,
+            FIRST_MINT_START_TIMESTAMP,
+            FIRST_MINT_END_TIMESTAMP,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_TOKEN_DISPLAY_NAME,
+            FIRST_TOKEN_TICKER,
+            FIRST_TAGS,
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            true,
+            None,
+            &[],
+        )
+        .assert_ok();
+    nm_setup.b_mock.set_esdt_local_roles(
+        nm_setup.nm_wrapper.address_ref(),
+        FIRST_TOKEN_ID,
+        &[EsdtLocalRole::NftCreate][..],
+    );
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_ok();
+
+    // placeholder attributes until reveal, which don't depend on the NFT's ID
+    let placeholder_attributes = format!(
+        "metadata:https://ipfs.io/ipfs/{}/collection.json;tags:funny,sad,memes",
+        String::from_utf8(FIRST_COLLECTION_HASH.to_vec()).unwrap()
+    );
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        FIRST_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        Some(&placeholder_attributes),
+    );
+
+    let placeholder_collection_uri = format!(
+        "https://ipfs.io/ipfs/{}/collection.json",
+        String::from_utf8(FIRST_COLLECTION_HASH.to_vec()).unwrap()
+    );
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            assert!(!result.brand_info.is_revealed);
+
+            // placeholder NFTs only get the collection URI
+            let token_data = sc.blockchain().get_esdt_token_data(
+                &managed_address!(&first_user_addr),
+                &TokenIdentifier::from_esdt_bytes(FIRST_TOKEN_ID),
+                1,
+            );
+            let uris: Vec<String> = token_data
+                .uris
+                .iter()
+                .map(|uri| managed_buffer_to_string(&uri))
+                .collect();
+            assert_eq!(uris, vec![placeholder_collection_uri.clone()]);
+            assert_eq!(
+                sc.unrevealed_nft_id(&managed_buffer!(FIRST_BRAND_ID), 1)
+                    .get(),
+                2
+            );
+        })
+        .assert_ok();
+
+    // try reveal NFTs before brand reveal
+    nm_setup
+        .call_reveal_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1])
+        .assert_user_error("Brand not revealed yet");
+
+    // try reveal with an already registered hash
+    nm_setup
+        .call_reveal_brand(FIRST_BRAND_ID, FIRST_COLLECTION_HASH)
+        .assert_user_error("Collection hash already exists");
+
+    nm_setup
+        .call_reveal_brand(FIRST_BRAND_ID, THIRD_COLLECTION_HASH)
+        .assert_ok();
+
+    nm_setup
+        .call_reveal_brand(FIRST_BRAND_ID, SECOND_COLLECTION_HASH)
+        .assert_user_error("Brand already revealed");
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            assert!(result.brand_info.is_revealed);
+            assert_eq!(
+                result.brand_info.collection_hash,
+                managed_buffer!(THIRD_COLLECTION_HASH)
+            );
+            assert!(!sc
+                .registered_collection_hashes()
+                .contains(&managed_buffer!(FIRST_COLLECTION_HASH)));
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_reveal_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1])
+        .assert_user_error("NFT update attributes and add URI roles not set");
+
+    nm_setup.b_mock.set_esdt_local_roles(
+        nm_setup.nm_wrapper.address_ref(),
+        FIRST_TOKEN_ID,
+        &[
+            EsdtLocalRole::NftCreate,
+            EsdtLocalRole::NftUpdateAttributes,
+            EsdtLocalRole::NftAddUri,
+        ][..],
+    );
+    nm_setup
+        .call_reveal_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1])
+        .assert_ok();
+
+    let revealed_attributes = format!(
+        "metadata:{}/2.json;tags:funny,sad,memes",
+        String::from_utf8(THIRD_COLLECTION_HASH.to_vec()).unwrap()
+    );
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        FIRST_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        None::<&String>,
+    );
+
+    // the mock keeps the metadata of the holder's previous instance on transfer,
+    // so the updated attributes are checked on the SC's copy
+    nm_setup.b_mock.check_nft_balance(
+        nm_setup.nm_wrapper.address_ref(),
+        FIRST_TOKEN_ID,
+        1,
+        &rust_biguint!(0),
+        Some(&revealed_attributes),
+    );
+
+    // the real URIs are appended after the placeholder one
+    let revealed_hash = String::from_utf8(THIRD_COLLECTION_HASH.to_vec()).unwrap();
+    let sc_addr = nm_setup.nm_wrapper.address_ref().clone();
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let token_data = sc.blockchain().get_esdt_token_data(
+                &managed_address!(&sc_addr),
+                &TokenIdentifier::from_esdt_bytes(FIRST_TOKEN_ID),
+                1,
+            );
+            let uris: Vec<String> = token_data
+                .uris
+                .iter()
+                .map(|uri| managed_buffer_to_string(&uri))
+                .collect();
+            assert_eq!(
+                uris,
+                vec![
+                    placeholder_collection_uri.clone(),
+                    format!("https://ipfs.io/ipfs/{}/2.png", revealed_hash),
+                    format!("https://ipfs.io/ipfs/{}/2.json", revealed_hash),
+                    format!("https://ipfs.io/ipfs/{}/collection.json", revealed_hash),
+                ]
+            );
+        })
+        .assert_ok();
+
+    // try reveal twice
+    nm_setup
+        .call_reveal_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1])
+        .assert_user_error("NFT already revealed");
+
+    // NFTs minted after reveal are final
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert!(sc
+                .unrevealed_nft_id(&managed_buffer!(FIRST_BRAND_ID), 2)
+                .is_empty());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn uri_templates_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_tier = FIRST_TIERS[0];
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+
+    nm_setup.create_default_brands();
+
+    // try create brand with a template without ID
+    nm_setup
+        .call_create_new_brand(
+            THIRD_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+            b"png",
+            0,
+            FIRST_MINT_START_TIMESTAMP,
+            FIRST_MINT_END_TIMESTAMP,
+            b"EGLD",
+            1,
+            b"",
+            b"TICKER",
+            &[],
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            false,
+            Some((
+                b"https://arweave.net/{hash}/{id}.{ext}",
+                b"ar://{hash}/collection.json",
+            )),
+            &[],
+        )
+        .assert_user_error("Invalid URI template");
+
+    nm_setup
+        .call_create_new_brand(
+            THIRD_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+            b"png",
+            0,
+            FIRST_MINT_START_TIMESTAMP,
+            FIRST_MINT_END_TIMESTAMP,
+            b"EGLD",
+            1,
+            b"",
+            b"TICKER",
+            &[],
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            false,
+            Some((
+                b"https://arweave.net/{hash}/{id}.{ext}",
+                b"ar://{hash}/{id}.json",
+            )),
+            &[],
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.clear_uri_templates_for_brand(managed_buffer!(b"unknownBrand"));
+        })
+        .assert_user_error("Invalid Brand ID");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_default_uri_templates(
+                managed_buffer!(b"https://gateway.pinata.cloud/ipfs/{hash}/{id}.{ext}"),
+                managed_buffer!(b"ipfs://{hash}/{id}.json"),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let first_brand_info = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            assert_eq!(
+                first_brand_info.uri_templates.file_uri,
+                managed_buffer!(b"https://gateway.pinata.cloud/ipfs/{hash}/{id}.{ext}")
+            );
+
+            let third_brand_id = managed_buffer!(THIRD_BRAND_ID);
+            let uri_templates = sc.get_uri_templates(&third_brand_id);
+            let coll_hash = managed_buffer!(THIRD_COLLECTION_HASH);
+            let nft_main_file_uri =
+                sc.build_nft_main_file_uri(&uri_templates, &coll_hash, 7, &managed_buffer!(b"png"));
+            assert_eq!(
+                managed_buffer_to_string(&nft_main_file_uri),
+                "https://arweave.net/QmPZ9gcCEpqKTo6aq61g2nXGUhM4iCL3ewB6LDXZCtioEB/7.png"
+            );
+
+            let collection_json_uri = sc.build_collection_json_file_uri(&uri_templates, &coll_hash);
+            assert_eq!(
+                managed_buffer_to_string(&collection_json_uri),
+                "https://arweave.net/QmPZ9gcCEpqKTo6aq61g2nXGUhM4iCL3ewB6LDXZCtioEB/collection.json"
+            );
+
+            let attributes =
+                sc.build_nft_attributes(&uri_templates, &coll_hash, &third_brand_id, 7);
+            assert_eq!(
+                managed_buffer_to_string(&attributes),
+                "metadata:ar://QmPZ9gcCEpqKTo6aq61g2nXGUhM4iCL3ewB6LDXZCtioEB/7.json"
+            );
+        })
+        .assert_ok();
+
+    // minted NFTs follow the contract-wide templates
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_ok();
+
+    let expected_attributes =
+        "metadata:ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/2.json;tags:funny,sad,memes"
+            .to_string();
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        FIRST_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        Some(&expected_attributes),
+    );
+
+    // try set invalid template
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_uri_templates_for_brand(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(b""),
+                managed_buffer!(b"{hash}/{id}.json"),
+            );
+        })
+        .assert_user_error("Invalid URI template");
+
+    // brand falls back to the contract-wide templates once cleared
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.clear_uri_templates_for_brand(managed_buffer!(THIRD_BRAND_ID));
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let uri_templates = sc.get_uri_templates(&managed_buffer!(THIRD_BRAND_ID));
+            assert_eq!(uri_templates, sc.default_uri_templates().get());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn collection_hash_formats_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    nm_setup.create_default_brands();
+
+    let invalid_hashes: &[&[u8]] = &[
+        b"FirstCollection_______________________________",
+        b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0",
+        b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdGG",
+        b"BAFYBEIGDYRZT5SFP7UDM7HU76UH7Y26NF3EFUYLQABF3OCLGTQY55FBZDI",
+        b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55",
+    ];
+    for invalid_hash in invalid_hashes {
+        nm_setup
+            .call_create_new_brand(
+                invalid_hash,
+                THIRD_BRAND_ID,
+                b"png",
+                0,
+                1,
+                2,
+                b"EGLD",
+                1,
+                b"",
+                b"TICKER",
+                &[],
+                FIRST_TIERS,
+                FIRST_NFT_AMOUNTS,
+                0,
+                0,
+                &[],
+                false,
+                None,
+                &[],
+            )
+            .assert_user_error("Invalid collection hash");
+    }
+
+    nm_setup
+        .call_create_new_brand(
+            CIDV1_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+            b"png",
+            0,
+            1,
+            2,
+            b"EGLD",
+            1,
+            b"",
+            b"TICKER",
+            &[],
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(THIRD_BRAND_ID));
+            assert_eq!(
+                result.brand_info.collection_hash,
+                managed_buffer!(CIDV1_COLLECTION_HASH)
+            );
+            assert!(sc
+                .registered_collection_hashes()
+                .contains(&managed_buffer!(CIDV1_COLLECTION_HASH)));
+
+            let nft_json_file_uri = sc.build_nft_json_file_uri(
+                &result.uri_templates,
+                &result.brand_info.collection_hash,
+                3,
+            );
+            assert_eq!(
+                managed_buffer_to_string(&nft_json_file_uri),
+                "https://ipfs.io/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi/3.json"
+            );
+        })
+        .assert_ok();
+
+    // brands stored before variable-length hashes are still decodable
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |_| {
+            let mut legacy_encoded = FIRST_COLLECTION_HASH.to_vec();
+            legacy_encoded
+                .extend_from_slice(&(FIRST_TOKEN_DISPLAY_NAME.len() as u32).to_be_bytes());
+            legacy_encoded.extend_from_slice(FIRST_TOKEN_DISPLAY_NAME);
+            legacy_encoded.extend_from_slice(&(FIRST_MEDIA_TYPE.len() as u32).to_be_bytes());
+            legacy_encoded.extend_from_slice(FIRST_MEDIA_TYPE);
+            legacy_encoded.extend_from_slice(&1u32.to_be_bytes());
+            legacy_encoded.push(10);
+            legacy_encoded.extend_from_slice(&FIRST_MINT_START_TIMESTAMP.to_be_bytes());
+            legacy_encoded.extend_from_slice(&FIRST_MINT_END_TIMESTAMP.to_be_bytes());
+            legacy_encoded.extend_from_slice(&5u64.to_be_bytes());
+
+            let expected_brand_info = BrandInfo::<DebugApi> {
+                collection_hash: managed_buffer!(FIRST_COLLECTION_HASH),
+                token_display_name: managed_buffer!(FIRST_TOKEN_DISPLAY_NAME),
+                media_type: managed_buffer!(FIRST_MEDIA_TYPE),
+                royalties: managed_biguint!(10),
+                mint_period: TimePeriod {
+                    start: FIRST_MINT_START_TIMESTAMP,
+                    end: FIRST_MINT_END_TIMESTAMP,
+                },
+                whitelist_expire_timestamp: 5,
+                is_revealed: true,
+            };
+            let decoded_brand_info =
+                BrandInfo::<DebugApi>::top_decode(legacy_encoded.as_slice()).unwrap();
+            assert_eq!(decoded_brand_info, expected_brand_info);
+
+            let mut encoded = Vec::new();
+            expected_brand_info.top_encode(&mut encoded).unwrap();
+            let decoded_brand_info = BrandInfo::<DebugApi>::top_decode(encoded.as_slice()).unwrap();
+            assert_eq!(decoded_brand_info, expected_brand_info);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn legacy_collection_hash_index_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+
+    // FIRST_COLLECTION_HASH is used as placeholder
+    nm_setup
+        .call_create_new_brand(
+            FIRST_COLLECTION_HASH,
+            FIRST_BRAND_ID,
+            FIRST_MEDIA_TYPE,
+            0,
+            FIRST_MINT_START_TIMESTAMP,
+            FIRST_MINT_END_TIMESTAMP,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_TOKEN_DISPLAY_NAME,
+            FIRST_TOKEN_TICKER,
+            FIRST_TAGS,
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            true,
+            None,
+            &[],
+        )
+        .assert_ok();
+    nm_setup
+        .call_create_new_brand(
+            SECOND_COLLECTION_HASH,
+            SECOND_BRAND_ID,
+            SECOND_MEDIA_TYPE,
+            0,
+            SECOND_MINT_START_TIMESTAMP,
+            SECOND_MINT_END_TIMESTAMP,
+            SECOND_MINT_PRICE_TOKEN_ID,
+            SECOND_MINT_PRICE_AMOUNT,
+            SECOND_TOKEN_DISPLAY_NAME,
+            SECOND_TOKEN_TICKER,
+            SECOND_TAGS,
+            SECOND_TIERS,
+            SECOND_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
+        )
+        .assert_ok();
+
+    // index the hashes by their raw bytes, as they were before variable-length hashes
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &nm_setup.owner_address,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                for hash in [FIRST_COLLECTION_HASH, SECOND_COLLECTION_HASH] {
+                    let index_mapper = sc.collection_hash_index(&managed_buffer!(hash));
+                    let index = index_mapper.get();
+                    index_mapper.clear();
+
+                    let legacy_hash = ManagedByteArray::new_from_bytes(hash.try_into().unwrap());
+                    sc.legacy_collection_hash_index(&legacy_hash).set(index);
+                }
+            },
+        )
+        .assert_ok();
+
+    nm_setup
+        .call_create_new_brand(
+            SECOND_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+            b"png",
+            0,
+            1,
+            2,
+            b"EGLD",
+            1,
+            b"",
+            b"TICKER",
+            &[],
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
+        )
+        .assert_user_error("Collection hash already exists");
+    nm_setup
+        .call_reveal_brand(FIRST_BRAND_ID, SECOND_COLLECTION_HASH)
+        .assert_user_error("Collection hash already exists");
+
+    // the placeholder hash is removed from the set on reveal
+    nm_setup
+        .call_reveal_brand(FIRST_BRAND_ID, THIRD_COLLECTION_HASH)
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let hashes_mapper = sc.registered_collection_hashes();
+            assert_eq!(hashes_mapper.len(), 2);
+            assert!(!hashes_mapper.contains(&managed_buffer!(FIRST_COLLECTION_HASH)));
+            assert!(hashes_mapper.contains(&managed_buffer!(THIRD_COLLECTION_HASH)));
+
+            let legacy_hash =
+                ManagedByteArray::new_from_bytes(FIRST_COLLECTION_HASH.try_into().unwrap());
+            assert!(sc.legacy_collection_hash_index(&legacy_hash).is_empty());
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_create_new_brand(
+            FIRST_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+            b"png",
+            0,
+            1,
+            2,
+            b"EGLD",
+            1,
+            b"",
+            b"TICKER",
+            &[],
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            false,
+            None,
+            &[],
+        )
+        .assert_ok();
+}
+
+#[test]
+fn brand_editing_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_tier = FIRST_TIERS[0];
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+
+    nm_setup.create_default_brands();
+
+    // change price before mint
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(first_tier),
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT * 2),
+            );
+            sc.set_tags_for_brand(
+                managed_buffer!(FIRST_BRAND_ID),
+                MultiValueEncoded::from(ManagedVec::from_single_item(managed_buffer!(b"edited"))),
+            );
+            sc.set_royalties_for_brand(managed_buffer!(FIRST_BRAND_ID), managed_biguint!(500));
+            sc.set_token_display_name(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(b"EditedToken"),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_royalties_for_brand(managed_buffer!(FIRST_BRAND_ID), managed_biguint!(10_001));
+        })
+        .assert_user_error("Royalties cannot be over 100%");
+
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"public",
+                start_timestamp: FIRST_MINT_START_TIMESTAMP,
+                end_timestamp: FIRST_MINT_END_TIMESTAMP - 1_000,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[],
+            },
+        )
+        .assert_ok();
+
+    // try shrink mint period, phase would be outside it
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_mint_period(
+                managed_buffer!(FIRST_BRAND_ID),
+                FIRST_MINT_START_TIMESTAMP,
+                FIRST_MINT_END_TIMESTAMP - 2_000,
+            );
+        })
+        .assert_user_error("Phase must be within the mint period");
+
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    // try change price during mint
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(first_tier),
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT),
+            );
+        })
+        .assert_user_error("May not change price during mint unless paused");
+
+    // try move start after mint started
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_mint_period(
+                managed_buffer!(FIRST_BRAND_ID),
+                FIRST_MINT_START_TIMESTAMP + 1,
+                FIRST_MINT_END_TIMESTAMP,
+            );
+        })
+        .assert_user_error("May not change start after mint started");
+
+    // extend mint
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_mint_period(
+                managed_buffer!(FIRST_BRAND_ID),
+                FIRST_MINT_START_TIMESTAMP,
+                FIRST_MINT_END_TIMESTAMP + 1_000,
+            );
+        })
+        .assert_ok();
+
+    // try buy at old price
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Invalid payment");
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT * 2,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_ok();
+
+    let expected_attributes = format!(
+        "metadata:{}/2.json;tags:edited",
+        String::from_utf8(FIRST_COLLECTION_HASH.to_vec()).unwrap()
+    );
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        FIRST_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        Some(&expected_attributes),
+    );
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            assert_eq!(
+                result.brand_info.mint_period,
+                TimePeriod {
+                    start: FIRST_MINT_START_TIMESTAMP,
+                    end: FIRST_MINT_END_TIMESTAMP + 1_000,
+                }
+            );
+            assert_eq!(result.brand_info.royalties, managed_biguint!(500));
+            assert_eq!(
+                result.brand_info.token_display_name,
+                managed_buffer!(b"EditedToken")
+            );
+        })
+        .assert_ok();
+
+    // try edit as non-admin
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_royalties_for_brand(managed_buffer!(FIRST_BRAND_ID), managed_biguint!(0));
+            },
+        )
+        .assert_user_error("Item not whitelisted");
+}
+
+#[test]
+fn pause_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_tier = FIRST_TIERS[0];
+    let second_tier = FIRST_TIERS[1];
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    // try pause as non-admin
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.pause_contract();
+            },
+        )
+        .assert_user_error("Item not whitelisted");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.pause_contract();
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Contract is paused");
+    nm_setup
+        .call_giveaway(
+            FIRST_BRAND_ID,
+            first_tier,
+            vec![(first_user_addr.clone(), 1)],
+        )
+        .assert_user_error("Contract is paused");
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(SECOND_BRAND_ID));
+            assert!(result.is_paused);
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.unpause_contract();
+            sc.pause_brand(managed_buffer!(FIRST_BRAND_ID));
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Brand is paused");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.unpause_brand(managed_buffer!(FIRST_BRAND_ID));
+            sc.pause_tier(managed_buffer!(FIRST_BRAND_ID), managed_buffer!(first_tier));
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Tier is paused");
+
+    // other tiers are not affected
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            second_tier,
+            1,
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            assert!(!result.is_paused);
+            assert!(result.tier_info_entries[0].is_paused);
+            assert!(!result.tier_info_entries[1].is_paused);
+        })
+        .assert_ok();
+
+    // price may be changed during mint while paused
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(first_tier),
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT * 3),
+            );
+            sc.unpause_tier(managed_buffer!(FIRST_BRAND_ID), managed_buffer!(first_tier));
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT * 3,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_ok();
+}
+
+#[test]
+fn failed_issue_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+
+    nm_setup.set_pending_brand_creation(FIRST_BRAND_ID, FIRST_COLLECTION_HASH, &first_user_addr, 1);
+    nm_setup
+        .call_issue_callback(
+            FIRST_BRAND_ID,
+            FIRST_COLLECTION_HASH,
+            &first_user_addr,
+            1,
+            Err(b"insufficient funds"),
+        )
+        .assert_ok();
+
+    // issue cost refunded to the original caller
+    nm_setup.b_mock.check_egld_balance(
+        &first_user_addr,
+        &(rust_biguint!(USER_EGLD_BALANCE) + rust_biguint!(ISSUE_COST)),
+    );
+    nm_setup
+        .b_mock
+        .check_egld_balance(nm_setup.nm_wrapper.address_ref(), &rust_biguint!(0));
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert!(!sc
+                .registered_brands()
+                .contains(&managed_buffer!(FIRST_BRAND_ID)));
+            assert!(!sc
+                .registered_collection_hashes()
+                .contains(&managed_buffer!(FIRST_COLLECTION_HASH)));
+            assert!(sc
+                .nft_tiers_for_brand(&managed_buffer!(FIRST_BRAND_ID))
+                .is_empty());
+            assert!(sc
+                .temporary_callback_storage(&managed_buffer!(FIRST_BRAND_ID))
+                .is_empty());
+        })
+        .assert_ok();
+
+    // same brand may be created again
+    nm_setup.create_default_brands();
+
+    // callback never arrived
+    nm_setup.set_pending_brand_creation(b"pending", CUSTOM_COLLECTION_HASH, &first_user_addr, 2);
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.clear_pending_brand_creation(managed_buffer!(b"pending"));
+            },
+        )
+        .assert_user_error("Item not whitelisted");
+
+    let owner_addr = nm_setup.owner_address.clone();
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.clear_pending_brand_creation(managed_buffer!(b"pending"));
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.clear_pending_brand_creation(managed_buffer!(b"pending"));
+        })
+        .assert_user_error("No pending brand creation");
+
+    // late callbacks leave the brand alone
+    nm_setup
+        .call_issue_callback(
+            b"pending",
+            CUSTOM_COLLECTION_HASH,
+            &first_user_addr,
+            2,
+            Ok(b"LATE-123456"),
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .set_egld_balance(&owner_addr, &rust_biguint!(ISSUE_COST));
+    nm_setup
+        .call_issue_callback(
+            b"pending",
+            CUSTOM_COLLECTION_HASH,
+            &first_user_addr,
+            2,
+            Err(b"insufficient funds"),
+        )
+        .assert_ok();
+
+    // the failed issue's cost is still refunded
+    nm_setup.b_mock.check_egld_balance(
+        &first_user_addr,
+        &(rust_biguint!(USER_EGLD_BALANCE) + rust_biguint!(2 * ISSUE_COST)),
+    );
+    nm_setup
+        .b_mock
+        .check_egld_balance(nm_setup.nm_wrapper.address_ref(), &rust_biguint!(0));
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert!(!sc
+                .registered_brands()
+                .contains(&managed_buffer!(b"pending")));
+            assert!(!sc
+                .registered_collection_hashes()
+                .contains(&managed_buffer!(CUSTOM_COLLECTION_HASH)));
+            assert!(sc.nft_token(&managed_buffer!(b"pending")).is_empty());
+        })
+        .assert_ok();
+
+    // the stale callback doesn't consume the brand's new creation
+    nm_setup.set_pending_brand_creation(b"pending", CUSTOM_COLLECTION_HASH, &first_user_addr, 3);
+    nm_setup
+        .call_issue_callback(
+            b"pending",
+            CUSTOM_COLLECTION_HASH,
+            &first_user_addr,
+            2,
+            Ok(b"LATE-123456"),
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert!(sc.nft_token(&managed_buffer!(b"pending")).is_empty());
+            assert!(!sc
+                .temporary_callback_storage(&managed_buffer!(b"pending"))
+                .is_empty());
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_issue_callback(
+            b"pending",
+            CUSTOM_COLLECTION_HASH,
+            &first_user_addr,
+            3,
+            Ok(b"NEW-123456"),
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert_eq!(
+                sc.nft_token(&managed_buffer!(b"pending")).get_token_id(),
+                TokenIdentifier::from_esdt_bytes(b"NEW-123456")
+            );
+            assert!(sc
+                .temporary_callback_storage(&managed_buffer!(b"pending"))
+                .is_empty());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn existing_token_brand_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let gold_tier = CUSTOM_TIERS[0];
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(CUSTOM_MINT_START_TIMESTAMP);
+
+    // token already used by a brand
+    nm_setup
+        .call_register_brand_with_existing_token(
+            FIRST_TOKEN_ID,
+            CUSTOM_COLLECTION_HASH,
+            CUSTOM_BRAND_ID,
+        )
+        .assert_user_error("Token already used by another brand");
+
+    nm_setup
+        .call_register_brand_with_existing_token(
+            b"not-a-token",
+            CUSTOM_COLLECTION_HASH,
+            CUSTOM_BRAND_ID,
+        )
+        .assert_user_error("Invalid token ID");
+
+    nm_setup
+        .call_register_brand_with_existing_token(
+            EXISTING_TOKEN_ID,
+            CUSTOM_COLLECTION_HASH,
+            CUSTOM_BRAND_ID,
+        )
+        .assert_ok();
+
+    nm_setup
+        .call_register_brand_with_existing_token(
+            EXISTING_TOKEN_ID,
+            THIRD_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+        )
+        .assert_user_error("Token already used by another brand");
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            CUSTOM_MINT_PRICE_TOKEN_ID,
+            CUSTOM_MINT_PRICE_AMOUNT,
+            CUSTOM_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_user_error("Token roles not confirmed");
+
+    // brands with issued tokens need no confirmation
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert!(!sc
+                .needs_token_role_confirmation(&managed_buffer!(FIRST_BRAND_ID))
+                .get());
+            assert!(sc
+                .needs_token_role_confirmation(&managed_buffer!(CUSTOM_BRAND_ID))
+                .get());
+        })
+        .assert_ok();
+
+    // role not transferred yet
+    let owner_addr = nm_setup.owner_address.clone();
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.confirm_token_roles(managed_buffer!(CUSTOM_BRAND_ID));
+        })
+        .assert_user_error("NFT create role not set");
+
+    nm_setup.b_mock.set_esdt_local_roles(
+        nm_setup.nm_wrapper.address_ref(),
+        EXISTING_TOKEN_ID,
+        &[EsdtLocalRole::NftCreate][..],
+    );
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.confirm_token_roles(managed_buffer!(CUSTOM_BRAND_ID));
+        })
+        .assert_ok();
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            CUSTOM_MINT_PRICE_TOKEN_ID,
+            CUSTOM_MINT_PRICE_AMOUNT,
+            CUSTOM_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &first_user_addr,
+            EXISTING_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            None,
+        );
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert_eq!(
+                sc.brand_id_for_token(&TokenIdentifier::from_esdt_bytes(EXISTING_TOKEN_ID))
+                    .get(),
+                managed_buffer!(CUSTOM_BRAND_ID)
+            );
+
+            let result = sc.get_brand_info_view(managed_buffer!(CUSTOM_BRAND_ID));
+            assert_eq!(
+                result.nft_token_id,
+                TokenIdentifier::from_esdt_bytes(EXISTING_TOKEN_ID)
+            );
+            assert_eq!(
+                result.tier_info_entries[0].available_nfts,
+                CUSTOM_NFT_AMOUNTS[0] - 1
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn tier_extensions_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+    let new_tier: &[u8] = b"platinum";
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    // IDs 1 to 35 are used by the initial tiers
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.extend_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                2,
+                30,
+                OptionalValue::None,
+            );
+        })
+        .assert_user_error("ID range overlaps existing IDs");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.extend_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                2,
+                35,
+                OptionalValue::Some(managed_buffer!(SECOND_COLLECTION_HASH)),
+            );
+        })
+        .assert_user_error("Collection hash already exists");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.extend_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                2,
+                35,
+                OptionalValue::Some(managed_buffer!(THIRD_COLLECTION_HASH)),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_tier_to_brand(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                1,
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT),
+                0,
+                37,
+                OptionalValue::None,
+            );
+        })
+        .assert_user_error("Duplicate tier name");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_tier_to_brand(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(new_tier),
+                1,
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT),
+                0,
+                37,
+                OptionalValue::None,
+            );
+            sc.add_tier_to_brand(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(b"diamond"),
+                1,
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT),
+                0,
+                38,
+                OptionalValue::None,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_tier_to_brand(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(b"emerald"),
+                1,
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT),
+                0,
+                39,
+                OptionalValue::None,
+            );
+        })
+        .assert_user_error("Max tiers per brand limit exceeded");
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            let gold_info = result
+                .tier_info_entries
+                .iter()
+                .find(|entry| entry.tier == managed_buffer!(gold_tier))
+                .unwrap();
+            assert_eq!(gold_info.total_nfts, FIRST_NFT_AMOUNTS[0] + 2);
+            assert_eq!(gold_info.available_nfts, FIRST_NFT_AMOUNTS[0] + 2);
+        })
+        .assert_ok();
+
+    // buy the whole gold tier, then the new tier
+    let total_gold_nfts = FIRST_NFT_AMOUNTS[0] + 2;
+    for _ in 0..total_gold_nfts {
+        nm_setup
+            .call_buy_random_nft(
+                &first_user_addr,
+                FIRST_MINT_PRICE_TOKEN_ID,
+                FIRST_MINT_PRICE_AMOUNT,
+                FIRST_BRAND_ID,
+                gold_tier,
+                1,
+            )
+            .assert_ok();
+    }
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_user_error("Not enough NFTs available");
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            new_tier,
+            1,
+        )
+        .assert_ok();
+
+    let mut minted_ids = Vec::new();
+    for nonce in 1..=(total_gold_nfts + 1) as u64 {
+        let attributes = nm_setup
+            .b_mock
+            .get_nft_attributes::<Vec<u8>>(&first_user_addr, FIRST_TOKEN_ID, nonce)
+            .unwrap();
+        let attributes = String::from_utf8(attributes).unwrap();
+        let hash_and_id = attributes
+            .strip_prefix("metadata:")
+            .unwrap()
+            .split(".json")
+            .next()
+            .unwrap();
+        let (collection_hash, nft_id) = hash_and_id.split_once('/').unwrap();
+        let nft_id: usize = nft_id.parse().unwrap();
+
+        let expected_hash = if nft_id > 35 && nft_id <= 37 {
+            THIRD_COLLECTION_HASH
+        } else {
+            FIRST_COLLECTION_HASH
+        };
+        assert_eq!(collection_hash.as_bytes(), expected_hash);
+
+        minted_ids.push(nft_id);
+    }
+
+    minted_ids.sort_unstable();
+    assert_eq!(minted_ids, vec![1, 2, 3, 4, 5, 36, 37, 38]);
+}
+
+#[test]
+fn close_mint_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let treasury_addr = nm_setup.second_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            FIRST_TIERS[0],
+            1,
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.close_mint(managed_buffer!(FIRST_BRAND_ID), OptionalValue::None);
+            },
+        )
+        .assert_user_error("Item not whitelisted");
+
+    // remaining NFTs go to the treasury
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let status = sc.close_mint(
+                managed_buffer!(FIRST_BRAND_ID),
+                OptionalValue::Some(managed_address!(&treasury_addr)),
+            );
+            assert_eq!(status, OperationCompletionStatus::Completed);
+        })
+        .assert_ok();
+
+    let total_first_nfts: usize = FIRST_NFT_AMOUNTS.iter().sum();
+    for nonce in 2..=total_first_nfts as u64 {
+        nm_setup
+            .b_mock
+            .check_nft_balance::<ManagedBuffer<DebugApi>>(
+                &treasury_addr,
+                FIRST_TOKEN_ID,
+                nonce,
+                &rust_biguint!(1),
+                None,
+            );
+    }
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            FIRST_TIERS[1],
+            1,
+        )
+        .assert_user_error("Mint closed");
+    nm_setup
+        .call_giveaway(
+            FIRST_BRAND_ID,
+            FIRST_TIERS[1],
+            vec![(first_user_addr.clone(), 1)],
+        )
+        .assert_user_error("Mint closed");
+
+    // remaining NFTs are retired
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let status = sc.close_mint(managed_buffer!(SECOND_BRAND_ID), OptionalValue::None);
+            assert_eq!(status, OperationCompletionStatus::Completed);
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            for brand_id in [FIRST_BRAND_ID, SECOND_BRAND_ID] {
+                let result = sc.get_brand_info_view(managed_buffer!(brand_id));
+                assert!(result.is_mint_closed);
+                for tier_info in result.tier_info_entries.iter() {
+                    assert_eq!(tier_info.available_nfts, 0);
+                    assert_eq!(sc.get_available_nfts(&result.brand_id, &tier_info.tier), 0);
+                }
+            }
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &treasury_addr,
+            SECOND_TOKEN_ID,
+            1,
+            &rust_biguint!(0),
+            None,
+        );
+}
+
+#[test]
+fn mystery_tier_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let mystery_price = 500u64;
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(mystery_price),
+            |sc| {
+                let _ = sc.buy_mystery_tier_nft(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    OptionalValue::None,
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("Brand has no mystery tier");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut tier_odds = MultiValueEncoded::new();
+            tier_odds.push((managed_buffer!(b"platinum"), 1u32).into());
+
+            sc.set_mystery_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_token_id!(EGLD_TOKEN_ID),
+                managed_biguint!(mystery_price),
+                tier_odds,
+            );
+        })
+        .assert_user_error("Invalid tier");
+
+    // only gold may be picked
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut tier_odds = MultiValueEncoded::new();
+            tier_odds.push((managed_buffer!(FIRST_TIERS[0]), 1u32).into());
+            tier_odds.push((managed_buffer!(FIRST_TIERS[1]), 0u32).into());
+
+            sc.set_mystery_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_token_id!(EGLD_TOKEN_ID),
+                managed_biguint!(mystery_price),
+                tier_odds,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(mystery_price),
+            |sc| {
+                let _ = sc.buy_mystery_tier_nft(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    OptionalValue::Some(2),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("Invalid payment");
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(mystery_price * 2),
+            |sc| {
+                let result = sc.buy_mystery_tier_nft(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    OptionalValue::Some(2),
+                    OptionalValue::None,
+                );
+
+                let result: Vec<_> = result.into_iter().collect();
+                assert_eq!(result.len(), 2);
+                for entry in result {
+                    let (tier, payment) = entry.into_tuple();
+                    assert_eq!(tier, managed_buffer!(FIRST_TIERS[0]));
+                    assert_eq!(
+                        payment.token_identifier,
+                        TokenIdentifier::from_esdt_bytes(FIRST_TOKEN_ID)
+                    );
+                }
+            },
+        )
+        .assert_ok();
+
+    // weighted by remaining NFTs, paused tiers are skipped
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_mystery_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_token_id!(EGLD_TOKEN_ID),
+                managed_biguint!(mystery_price),
+                MultiValueEncoded::new(),
+            );
+            sc.pause_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(FIRST_TIERS[0]),
+            );
+            sc.pause_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(FIRST_TIERS[1]),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(mystery_price * 2),
+            |sc| {
+                let result = sc.buy_mystery_tier_nft(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    OptionalValue::Some(2),
+                    OptionalValue::None,
+                );
+
+                for entry in result {
+                    let (tier, _) = entry.into_tuple();
+                    assert_eq!(tier, managed_buffer!(FIRST_TIERS[2]));
+                }
+            },
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            let available_nfts: Vec<usize> = result
+                .tier_info_entries
+                .iter()
+                .map(|entry| entry.available_nfts)
+                .collect();
+            assert_eq!(
+                available_nfts,
+                vec![
+                    FIRST_NFT_AMOUNTS[0] - 2,
+                    FIRST_NFT_AMOUNTS[1],
+                    FIRST_NFT_AMOUNTS[2] - 2
+                ]
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn mystery_tier_wallet_limit_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let mystery_price = 500u64;
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_max_nfts_per_wallet_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(FIRST_TIERS[0]),
+                1,
+            );
+
+            let mut tier_odds = MultiValueEncoded::new();
+            tier_odds.push((managed_buffer!(FIRST_TIERS[0]), 1u32).into());
+            tier_odds.push((managed_buffer!(FIRST_TIERS[1]), 1u32).into());
+            sc.set_mystery_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_token_id!(EGLD_TOKEN_ID),
+                managed_biguint!(mystery_price),
+                tier_odds,
+            );
+        })
+        .assert_ok();
+
+    // gold is skipped once the buyer reached its limit
+    for _ in 0..3 {
+        nm_setup
+            .b_mock
+            .execute_tx(
+                &first_user_addr,
+                &nm_setup.nm_wrapper,
+                &rust_biguint!(mystery_price * 2),
+                |sc| {
+                    let _ = sc.buy_mystery_tier_nft(
+                        managed_buffer!(FIRST_BRAND_ID),
+                        OptionalValue::Some(2),
+                        OptionalValue::None,
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(FIRST_BRAND_ID);
+            let buyer = managed_address!(&first_user_addr);
+            assert_eq!(sc.minted_count_for_address(&brand_id, &buyer).get(), 6);
+            assert!(
+                sc.minted_count_for_address_in_tier(
+                    &brand_id,
+                    &managed_buffer!(FIRST_TIERS[0]),
+                    &buyer
+                )
+                .get()
+                    <= 1
+            );
+        })
+        .assert_ok();
+
+    // only gold may be picked, so nothing is left for the buyer
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut tier_odds = MultiValueEncoded::new();
+            tier_odds.push((managed_buffer!(FIRST_TIERS[0]), 1u32).into());
+            sc.set_mystery_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_token_id!(EGLD_TOKEN_ID),
+                managed_biguint!(mystery_price),
+                tier_odds,
+            );
+        })
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(mystery_price),
+            |sc| {
+                let _ = sc.buy_mystery_tier_nft(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    OptionalValue::None,
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("No NFTs available for mystery tier");
+}
+
+#[test]
+fn dutch_auction_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let second_user_addr = nm_setup.second_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+
+    let auction_strategy = |start_price: u64, floor_price: u64| {
+        PricingStrategy::DutchAuction(DutchAuction {
+            start_price: managed_biguint!(start_price),
+            floor_price: managed_biguint!(floor_price),
+            price_step: managed_biguint!(2_000),
+            step_duration: 100,
+            start_timestamp: FIRST_MINT_START_TIMESTAMP,
+            rebate_to_clearing_price: true,
+        })
+    };
+
+    nm_setup.create_default_brands();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                auction_strategy(1_000, 10_000),
+            );
+        })
+        .assert_user_error("Start price must not be lower than floor price");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                auction_strategy(10_000, 1_000),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+    check_current_price(&mut nm_setup, gold_tier, 10_000);
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            10_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.claim_auction_rebate(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    managed_buffer!(gold_tier),
+                );
+            },
+        )
+        .assert_user_error("Auction not finished");
+
+    // two steps later
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP + 250);
+    check_current_price(&mut nm_setup, gold_tier, 6_000);
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            6_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            2,
+        )
+        .assert_user_error("Invalid payment");
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            12_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            2,
+        )
+        .assert_ok();
+
+    // price never goes below the floor
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP + 10_000);
+    check_current_price(&mut nm_setup, gold_tier, 1_000);
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            2_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            2,
+        )
+        .assert_ok();
+
+    // tier sold out, clearing price is 1_000
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let rebate = sc.claim_auction_rebate(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    managed_buffer!(gold_tier),
+                );
+                assert_eq!(rebate.amount, managed_biguint!(9_000));
+            },
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &second_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let rebate = sc.claim_auction_rebate(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    managed_buffer!(gold_tier),
+                );
+                assert_eq!(rebate.amount, managed_biguint!(10_000));
+            },
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &second_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.claim_auction_rebate(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    managed_buffer!(gold_tier),
+                );
+            },
+        )
+        .assert_user_error("Nothing to claim");
+
+    nm_setup.b_mock.check_egld_balance(
+        &first_user_addr,
+        &rust_biguint!(USER_EGLD_BALANCE - 3 * 1_000),
+    );
+    nm_setup.b_mock.check_egld_balance(
+        &second_user_addr,
+        &rust_biguint!(USER_EGLD_BALANCE - 2 * 1_000),
+    );
+
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.settle_auction(managed_buffer!(FIRST_BRAND_ID), managed_buffer!(gold_tier));
+
+                let mint_payments = sc
+                    .accumulated_mint_payments()
+                    .get(&managed_token_id!(EGLD_TOKEN_ID))
+                    .unwrap_or_default();
+                assert_eq!(mint_payments, managed_biguint!(5 * 1_000));
+            },
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &nm_setup.nm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.settle_auction(managed_buffer!(FIRST_BRAND_ID), managed_buffer!(gold_tier));
+            },
+        )
+        .assert_user_error("Auction already settled");
+    nm_setup
+        .b_mock
+        .check_egld_balance(nm_setup.nm_wrapper.address_ref(), &rust_biguint!(5 * 1_000));
+
+    // the auction's price token may not change after sales
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.pause_tier(managed_buffer!(FIRST_BRAND_ID), managed_buffer!(gold_tier));
+        })
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(1_000),
+            );
+        })
+        .assert_user_error("May not change pricing after auction sales");
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(1_000)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                price_options,
+            );
+        })
+        .assert_user_error("May not change pricing after auction sales");
+
+    // no more sales once settled, even with more supply
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.unpause_tier(managed_buffer!(FIRST_BRAND_ID), managed_buffer!(gold_tier));
+            sc.extend_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                2,
+                35,
+                OptionalValue::None,
+            );
+        })
+        .assert_ok();
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            1_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_user_error("Auction already settled");
+}
+
+#[test]
+fn bonding_curve_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let silver_tier = FIRST_TIERS[1];
+    let bronze_tier = FIRST_TIERS[2];
+
+    nm_setup.create_default_brands();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+                PricingStrategy::BondingCurve(BondingCurve {
+                    kind: CurveKind::Linear,
+                    step_increase: managed_biguint!(500),
+                    nfts_per_step: 0,
+                }),
+            );
+        })
+        .assert_user_error("Invalid NFTs per step");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+                PricingStrategy::BondingCurve(BondingCurve {
+                    kind: CurveKind::Linear,
+                    step_increase: managed_biguint!(500),
+                    nfts_per_step: 3,
+                }),
+            );
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                PricingStrategy::BondingCurve(BondingCurve {
+                    kind: CurveKind::Exponential,
+                    step_increase: managed_biguint!(1_000),
+                    nfts_per_step: 1,
+                }),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    // linear, 1_000 for the first 3 NFTs, 1_500 for the next 3
+    check_price_quote(&mut nm_setup, bronze_tier, 2, 2_000);
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            2_000,
+            FIRST_BRAND_ID,
+            bronze_tier,
+            2,
+        )
+        .assert_ok();
+
+    check_price_quote(&mut nm_setup, bronze_tier, 2, 2_500);
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            2_000,
+            FIRST_BRAND_ID,
+            bronze_tier,
+            2,
+        )
+        .assert_user_error("Invalid payment");
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            2_500,
+            FIRST_BRAND_ID,
+            bronze_tier,
+            2,
+        )
+        .assert_ok();
+    check_current_price(&mut nm_setup, bronze_tier, 1_500);
+
+    // exponential, 10% more for every NFT
+    check_price_quote(&mut nm_setup, silver_tier, 2, 1_000 + 1_100);
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            2_100,
+            FIRST_BRAND_ID,
+            silver_tier,
+            2,
+        )
+        .assert_ok();
+    check_price_quote(&mut nm_setup, silver_tier, 2, 1_210 + 1_331);
+
+    // giveaways move along the curve as well
+    nm_setup
+        .call_giveaway(
+            FIRST_BRAND_ID,
+            silver_tier,
+            vec![(first_user_addr.clone(), 1)],
+        )
+        .assert_ok();
+    check_current_price(&mut nm_setup, silver_tier, 1_331);
+}
+
+#[test]
+fn price_token_change_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+    let silver_tier = FIRST_TIERS[1];
+    let bronze_tier = FIRST_TIERS[2];
+
+    nm_setup.create_default_brands();
+
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"presale",
+                start_timestamp: FIRST_MINT_START_TIMESTAMP,
+                end_timestamp: FIRST_MINT_START_TIMESTAMP + 1_000,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[(silver_tier, FIRST_MINT_PRICE_AMOUNT / 2)],
+            },
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+                PricingStrategy::BondingCurve(BondingCurve {
+                    kind: CurveKind::Linear,
+                    step_increase: managed_biguint!(500),
+                    nfts_per_step: 1,
+                }),
+            );
+        })
+        .assert_ok();
+
+    // the phase price and curve amounts are in the current token
+    for tier in [silver_tier, bronze_tier] {
+        nm_setup
+            .b_mock
+            .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_price_for_tier(
+                    managed_buffer!(FIRST_BRAND_ID),
+                    managed_buffer!(tier),
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(1_000),
+                );
+            })
+            .assert_user_error(
+                "May not change price token with phase prices or a pricing strategy",
+            );
+    }
+
+    // the amount may still change
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                managed_biguint!(FIRST_MINT_PRICE_AMOUNT * 2),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(1_000),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(FIRST_BRAND_ID);
+            assert_eq!(
+                sc.price_for_tier(&brand_id, &managed_buffer!(gold_tier))
+                    .get(),
+                MintPrice {
+                    token_id: managed_token_id!(USDC_TOKEN_ID),
+                    amount: managed_biguint!(1_000),
+                }
+            );
+            assert_eq!(
+                sc.phase_price_for_tier(
+                    &brand_id,
+                    &managed_buffer!(b"presale"),
+                    &managed_buffer!(silver_tier)
+                )
+                .get(),
+                MintPrice {
+                    token_id: managed_token_id!(FIRST_MINT_PRICE_TOKEN_ID),
+                    amount: managed_biguint!(FIRST_MINT_PRICE_AMOUNT / 2),
+                }
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn price_options_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+
+    nm_setup.create_default_brands();
+
+    let invalid_price_options: &[&[(&[u8], u64)]] = &[
+        &[(EGLD_TOKEN_ID, 5)],
+        &[(USDC_TOKEN_ID, 5_000), (USDC_TOKEN_ID, 6_000)],
+    ];
+    for price_options in invalid_price_options {
+        nm_setup
+            .call_create_new_brand(
+                THIRD_COLLECTION_HASH,
+                THIRD_BRAND_ID,
+                b"png",
+                0,
+                FIRST_MINT_START_TIMESTAMP,
+                FIRST_MINT_END_TIMESTAMP,
+                EGLD_TOKEN_ID,
+                1_000,
+                b"ThirdToken",
+                b"THIRD",
+                &[],
+                FIRST_TIERS,
+                FIRST_NFT_AMOUNTS,
+                0,
+                0,
+                &[],
+                false,
+                None,
+                price_options,
+            )
+            .assert_user_error("Duplicate price token");
+    }
+
+    nm_setup
+        .call_create_new_brand(
+            THIRD_COLLECTION_HASH,
+            THIRD_BRAND_ID,
+            b"png",
+            0,
+            FIRST_MINT_START_TIMESTAMP,
+            FIRST_MINT_END_TIMESTAMP,
+            EGLD_TOKEN_ID,
+            1_000,
+            b"ThirdToken",
+            b"THIRD",
+            &[],
+            FIRST_TIERS,
+            FIRST_NFT_AMOUNTS,
+            0,
+            0,
+            &[],
+            false,
+            None,
+            &[(USDC_TOKEN_ID, 5_000), (COMMUNITY_TOKEN_ID, 20)],
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            for tier in FIRST_TIERS {
+                let price_options_mapper = sc.extra_price_options_for_tier(
+                    &managed_buffer!(THIRD_BRAND_ID),
+                    &managed_buffer!(tier),
+                );
+                assert_eq!(price_options_mapper.len(), 2);
+                assert_eq!(
+                    price_options_mapper.get(&managed_token_id!(USDC_TOKEN_ID)),
+                    Some(managed_biguint!(5_000))
+                );
+                assert_eq!(
+                    price_options_mapper.get(&managed_token_id!(COMMUNITY_TOKEN_ID)),
+                    Some(managed_biguint!(20))
+                );
+            }
+        })
+        .assert_ok();
+
+    // edit the first brand's options
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+            price_options.push((managed_token_id!(EGLD_TOKEN_ID), managed_biguint!(5)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                price_options,
+            );
+        })
+        .assert_user_error("Duplicate price token");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+            price_options
+                .push((managed_token_id!(COMMUNITY_TOKEN_ID), managed_biguint!(20)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                price_options,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+    nm_setup
+        .b_mock
+        .set_esdt_balance(&first_user_addr, USDC_TOKEN_ID, &rust_biguint!(100_000));
+    nm_setup
+        .b_mock
+        .set_esdt_balance(&first_user_addr, COMMUNITY_TOKEN_ID, &rust_biguint!(1_000));
+
+    // may not edit options during mint unless paused
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_user_error("May not change price during mint unless paused");
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            USDC_TOKEN_ID,
+            10_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            2,
+        )
+        .assert_ok();
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            COMMUNITY_TOKEN_ID,
+            15,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_user_error("Invalid payment");
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            COMMUNITY_TOKEN_ID,
+            20,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+
+    // other tiers only accept their price token
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            USDC_TOKEN_ID,
+            5_000,
+            FIRST_BRAND_ID,
+            FIRST_TIERS[1],
+            1,
+        )
+        .assert_user_error("Invalid payment");
+
+    nm_setup.b_mock.check_esdt_balance(
+        nm_setup.nm_wrapper.address_ref(),
+        USDC_TOKEN_ID,
+        &rust_biguint!(10_000),
+    );
+    nm_setup.b_mock.check_esdt_balance(
+        nm_setup.nm_wrapper.address_ref(),
+        COMMUNITY_TOKEN_ID,
+        &rust_biguint!(20),
+    );
+    nm_setup.b_mock.check_egld_balance(
+        nm_setup.nm_wrapper.address_ref(),
+        &rust_biguint!(FIRST_MINT_PRICE_AMOUNT),
+    );
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let result = sc.get_brand_info_view(managed_buffer!(FIRST_BRAND_ID));
+            let gold_tier_info = result.tier_info_entries.first().unwrap();
+            assert_eq!(gold_tier_info.available_nfts, FIRST_NFT_AMOUNTS[0] - 4);
+
+            let mut expected_price_options = ManagedVec::<DebugApi, MintPrice<DebugApi>>::new();
+            for (token_id, amount) in [
+                (FIRST_MINT_PRICE_TOKEN_ID, FIRST_MINT_PRICE_AMOUNT),
+                (USDC_TOKEN_ID, 5_000),
+                (COMMUNITY_TOKEN_ID, 20),
+            ] {
+                expected_price_options.push(MintPrice {
+                    token_id: managed_token_id!(token_id),
+                    amount: managed_biguint!(amount),
+                });
+            }
+            assert_eq!(gold_tier_info.price_options, expected_price_options);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn price_options_with_bonding_curve_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let owner_addr = nm_setup.owner_address.clone();
+    let silver_tier = FIRST_TIERS[1];
+
+    nm_setup.create_default_brands();
+
+    let curve_strategy = || {
+        PricingStrategy::BondingCurve(BondingCurve {
+            kind: CurveKind::Linear,
+            step_increase: managed_biguint!(500),
+            nfts_per_step: 1,
+        })
+    };
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                curve_strategy(),
+            );
+        })
+        .assert_ok();
+
+    // a fixed USDC price would stop following the curve
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                price_options,
+            );
+        })
+        .assert_user_error(
+            "Extra price options may not be combined with a pricing strategy or phase prices",
+        );
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                PricingStrategy::Fixed,
+            );
+
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                price_options,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                curve_strategy(),
+            );
+        })
+        .assert_user_error(
+            "Extra price options may not be combined with a pricing strategy or phase prices",
+        );
+}
+
+#[test]
+fn price_options_with_dutch_auction_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+
+    nm_setup.create_default_brands();
+
+    let auction_strategy = || {
+        PricingStrategy::DutchAuction(DutchAuction {
+            start_price: managed_biguint!(10_000),
+            floor_price: managed_biguint!(1_000),
+            price_step: managed_biguint!(2_000),
+            step_duration: 100,
+            start_timestamp: FIRST_MINT_START_TIMESTAMP,
+            rebate_to_clearing_price: true,
+        })
+    };
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                price_options,
+            );
+        })
+        .assert_ok();
+
+    // paying with USDC would skip the auction price and its rebates
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                auction_strategy(),
+            );
+        })
+        .assert_user_error(
+            "Extra price options may not be combined with a pricing strategy or phase prices",
+        );
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                MultiValueEncoded::new(),
+            );
+            sc.set_pricing_strategy(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                auction_strategy(),
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                price_options,
+            );
+        })
+        .assert_user_error(
+            "Extra price options may not be combined with a pricing strategy or phase prices",
+        );
+}
+
+#[test]
+fn price_options_with_phase_prices_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+    let silver_tier = FIRST_TIERS[1];
+    let presale_end = FIRST_MINT_START_TIMESTAMP + 1_000;
+
+    nm_setup.create_default_brands();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                price_options,
+            );
+        })
+        .assert_ok();
+
+    // paying with USDC would ignore the phase price
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"presale",
+                start_timestamp: FIRST_MINT_START_TIMESTAMP,
+                end_timestamp: presale_end,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[(silver_tier, FIRST_MINT_PRICE_AMOUNT * 2)],
+            },
+        )
+        .assert_user_error(
+            "Extra price options may not be combined with a pricing strategy or phase prices",
+        );
+
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"presale",
+                start_timestamp: FIRST_MINT_START_TIMESTAMP,
+                end_timestamp: presale_end,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[(gold_tier, FIRST_MINT_PRICE_AMOUNT * 2)],
+            },
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut price_options = MultiValueEncoded::new();
+            price_options.push((managed_token_id!(USDC_TOKEN_ID), managed_biguint!(5_000)).into());
+
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                price_options,
+            );
+        })
+        .assert_user_error(
+            "Extra price options may not be combined with a pricing strategy or phase prices",
+        );
+}
+
+#[test]
+fn overpayment_and_multi_tier_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+    let silver_tier = FIRST_TIERS[1];
+    let bronze_tier = FIRST_TIERS[2];
+
+    nm_setup.create_default_brands();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(2_000),
+            );
+
+            let mut price_options = MultiValueEncoded::new();
+            price_options
+                .push((managed_token_id!(COMMUNITY_TOKEN_ID), managed_biguint!(10)).into());
+            sc.set_extra_price_options_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(silver_tier),
+                price_options,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+    nm_setup
+        .b_mock
+        .set_esdt_balance(&first_user_addr, USDC_TOKEN_ID, &rust_biguint!(100_000));
+    nm_setup
+        .b_mock
+        .set_esdt_balance(&first_user_addr, COMMUNITY_TOKEN_ID, &rust_biguint!(1_000));
+
+    // the excess is sent back
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT + 500,
+            FIRST_BRAND_ID,
+            bronze_tier,
+            1,
+        )
+        .assert_ok();
+    nm_setup.b_mock.check_egld_balance(
+        &first_user_addr,
+        &rust_biguint!(USER_EGLD_BALANCE - FIRST_MINT_PRICE_AMOUNT),
+    );
+    nm_setup.b_mock.check_egld_balance(
+        nm_setup.nm_wrapper.address_ref(),
+        &rust_biguint!(FIRST_MINT_PRICE_AMOUNT),
+    );
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            USDC_TOKEN_ID,
+            1_999,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_user_error("Invalid payment");
+
+    // multi-tier
+    nm_setup
+        .call_buy_random_nfts_multi_tier(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(gold_tier, 1), (silver_tier, 1)],
+            &[(USDC_TOKEN_ID, 2_000)],
+        )
+        .assert_user_error("Must send one payment per tier");
+    nm_setup
+        .call_buy_random_nfts_multi_tier(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(gold_tier, 2), (silver_tier, 1)],
+            &[(USDC_TOKEN_ID, 4_000), (COMMUNITY_TOKEN_ID, 10)],
+        )
+        .assert_user_error("Max NFTs per transaction limit exceeded");
+    nm_setup
+        .call_buy_random_nfts_multi_tier(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(gold_tier, 1), (silver_tier, 1)],
+            &[(COMMUNITY_TOKEN_ID, 10), (USDC_TOKEN_ID, 2_000)],
+        )
+        .assert_user_error("Invalid payment");
+    nm_setup
+        .call_buy_random_nfts_multi_tier(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(gold_tier, 1), (silver_tier, 1)],
+            &[(USDC_TOKEN_ID, 2_500), (COMMUNITY_TOKEN_ID, 15)],
+        )
+        .assert_ok();
+
+    nm_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        USDC_TOKEN_ID,
+        &rust_biguint!(100_000 - 2_000),
+    );
+    nm_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        COMMUNITY_TOKEN_ID,
+        &rust_biguint!(1_000 - 10),
+    );
+    nm_setup.b_mock.check_esdt_balance(
+        nm_setup.nm_wrapper.address_ref(),
+        USDC_TOKEN_ID,
+        &rust_biguint!(2_000),
+    );
+    nm_setup.b_mock.check_esdt_balance(
+        nm_setup.nm_wrapper.address_ref(),
+        COMMUNITY_TOKEN_ID,
+        &rust_biguint!(10),
+    );
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            for (tier, nft_amount) in FIRST_TIERS.iter().zip(FIRST_NFT_AMOUNTS.iter()) {
+                assert_eq!(
+                    sc.get_available_nfts(&managed_buffer!(FIRST_BRAND_ID), &managed_buffer!(tier)),
+                    nft_amount - 1
+                );
+            }
+        })
+        .assert_ok();
+}
+
+#[test]
+fn gift_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let second_user_addr = nm_setup.second_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let sc_addr = nm_setup.nm_wrapper.address_ref().clone();
+    let first_tier = FIRST_TIERS[0];
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .call_set_max_nfts_per_wallet(FIRST_BRAND_ID, None, 1)
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    nm_setup
+        .call_buy_random_nft_for(
+            &first_user_addr,
+            &sc_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+        )
+        .assert_user_error("Only user accounts are allowed to mint");
+
+    // the payer's limits are used by default
+    nm_setup
+        .call_buy_random_nft_for(
+            &first_user_addr,
+            &second_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &second_user_addr,
+            FIRST_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            None,
+        );
+    nm_setup.b_mock.check_egld_balance(
+        &first_user_addr,
+        &rust_biguint!(USER_EGLD_BALANCE - FIRST_MINT_PRICE_AMOUNT),
+    );
+
+    nm_setup
+        .call_buy_random_nft_for(
+            &first_user_addr,
+            &second_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+        )
+        .assert_user_error("Wallet limit exceeded for brand");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_gift_limits_party(managed_buffer!(FIRST_BRAND_ID), GiftLimitsParty::Recipient);
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_buy_random_nft_for(
+            &first_user_addr,
+            &second_user_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+        )
+        .assert_ok();
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+            1,
+        )
+        .assert_user_error("Wallet limit exceeded for brand");
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(FIRST_BRAND_ID);
+            assert_eq!(
+                sc.minted_count_for_address(&brand_id, &managed_address!(&first_user_addr))
+                    .get(),
+                1
+            );
+            assert_eq!(
+                sc.minted_count_for_address(&brand_id, &managed_address!(&second_user_addr))
+                    .get(),
+                1
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn sc_recipient_allowlist_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let first_tier = FIRST_TIERS[0];
+
+    nm_setup.create_default_brands();
+
+    let staking_sc_wrapper = nm_setup.b_mock.create_sc_account(
+        &rust_biguint!(0),
+        Some(&owner_addr),
+        nft_minter::contract_obj,
+        "staking sc path",
+    );
+    let staking_sc_addr = staking_sc_wrapper.address_ref().clone();
+
+    nm_setup
+        .call_giveaway(
+            FIRST_BRAND_ID,
+            first_tier,
+            vec![(staking_sc_addr.clone(), 1)],
+        )
+        .assert_user_error("Only user accounts are allowed to mint");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut args = MultiValueEncoded::new();
+            args.push(managed_address!(&staking_sc_addr));
+            sc.add_allowed_sc_recipients(args);
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_giveaway(
+            FIRST_BRAND_ID,
+            first_tier,
+            vec![(staking_sc_addr.clone(), 1)],
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+    nm_setup
+        .call_buy_random_nft_for(
+            &first_user_addr,
+            &staking_sc_addr,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &staking_sc_addr,
+            FIRST_TOKEN_ID,
+            2,
+            &rust_biguint!(1),
+            None,
+        );
+
+    // other contracts are still rejected
+    nm_setup
+        .call_buy_random_nft_for(
+            &first_user_addr,
+            &nm_setup.nm_wrapper.address_ref().clone(),
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            first_tier,
+        )
+        .assert_user_error("Only user accounts are allowed to mint");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut args = MultiValueEncoded::new();
+            args.push(managed_address!(&staking_sc_addr));
+            sc.remove_allowed_sc_recipients(args);
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_giveaway(FIRST_BRAND_ID, first_tier, vec![(staking_sc_addr, 1)])
+        .assert_user_error("Only user accounts are allowed to mint");
+}
+
+#[test]
+fn whitelist_quotas_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let second_user_addr = nm_setup.second_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+    let silver_tier = FIRST_TIERS[1];
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"presale",
+                start_timestamp: FIRST_MINT_START_TIMESTAMP,
+                end_timestamp: FIRST_MINT_END_TIMESTAMP,
+                whitelist_only: true,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[],
+            },
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut args = MultiValueEncoded::new();
+            args.push((managed_address!(&first_user_addr), 2).into());
+            sc.add_to_whitelist(managed_buffer!(FIRST_BRAND_ID), args);
+
+            let mut args = MultiValueEncoded::new();
+            args.push((managed_address!(&second_user_addr), 1).into());
+            sc.add_to_tier_whitelist(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                args,
+            );
+        })
+        .assert_ok();
+
+    check_whitelist_allowance(&mut nm_setup, &first_user_addr, None, Some(2));
+    check_whitelist_allowance(&mut nm_setup, &first_user_addr, Some(gold_tier), Some(2));
+    check_whitelist_allowance(&mut nm_setup, &second_user_addr, None, Some(0));
+    check_whitelist_allowance(&mut nm_setup, &second_user_addr, Some(gold_tier), Some(1));
+
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    // brand-wide quota is shared by all tiers
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            silver_tier,
+            1,
+        )
+        .assert_ok();
+    check_whitelist_allowance(&mut nm_setup, &first_user_addr, None, Some(1));
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT * 2,
+            FIRST_BRAND_ID,
+            gold_tier,
+            2,
+        )
+        .assert_user_error("Whitelist allowance exceeded");
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+    check_whitelist_allowance(&mut nm_setup, &first_user_addr, None, Some(0));
+
+    // tier quota only applies to its tier
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            silver_tier,
+            1,
+        )
+        .assert_user_error("Not in whitelist");
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+    nm_setup
+        .call_buy_random_nft(
+            &second_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_user_error("Whitelist allowance exceeded");
+    check_whitelist_allowance(&mut nm_setup, &second_user_addr, Some(gold_tier), Some(0));
+
+    // quota 0 removes the limit
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut args = MultiValueEncoded::new();
+            args.push((managed_address!(&first_user_addr), 0).into());
+            sc.add_to_whitelist(managed_buffer!(FIRST_BRAND_ID), args);
+
+            let mut args = MultiValueEncoded::new();
+            args.push(managed_address!(&second_user_addr));
+            sc.remove_from_tier_whitelist(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                args,
+            );
+        })
+        .assert_ok();
+    check_whitelist_allowance(&mut nm_setup, &first_user_addr, None, None);
+    check_whitelist_allowance(&mut nm_setup, &second_user_addr, Some(gold_tier), Some(0));
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            FIRST_MINT_PRICE_TOKEN_ID,
+            FIRST_MINT_PRICE_AMOUNT,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+}
+
+#[test]
+fn whitelist_pagination_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+
+    nm_setup.create_default_brands();
+
+    let users: Vec<Address> = (0..5)
+        .map(|_| nm_setup.b_mock.create_user_account(&rust_biguint!(0)))
+        .collect();
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let mut args = MultiValueEncoded::new();
+            for user in &users {
+                args.push((managed_address!(user), 1).into());
+            }
+            sc.add_to_whitelist(managed_buffer!(FIRST_BRAND_ID), args);
+
+            let mut args = MultiValueEncoded::new();
+            args.push((managed_address!(&users[0]), 1).into());
+            sc.add_to_tier_whitelist(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                args,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(FIRST_BRAND_ID);
+            assert_eq!(
+                sc.get_whitelist_size(brand_id.clone(), OptionalValue::None),
+                5
+            );
+            assert_eq!(
+                sc.get_whitelist_size(
+                    brand_id.clone(),
+                    OptionalValue::Some(managed_buffer!(gold_tier))
+                ),
+                1
+            );
+            assert!(sc.is_whitelisted(
+                brand_id.clone(),
+                managed_address!(&users[4]),
+                OptionalValue::None
+            ));
+            assert!(!sc.is_whitelisted(
+                brand_id.clone(),
+                managed_address!(&users[4]),
+                OptionalValue::Some(managed_buffer!(gold_tier))
+            ));
+
+            let expected_pages: &[(usize, usize, &[Address])] = &[
+                (0, 2, &users[0..2]),
+                (2, 2, &users[2..4]),
+                (4, 2, &users[4..5]),
+                (5, 2, &[]),
+                (100, usize::MAX, &[]),
+            ];
+            for (offset, limit, expected_users) in expected_pages {
+                let page: Vec<Address> = sc
+                    .get_mint_whitelist_page(brand_id.clone(), *offset, *limit, OptionalValue::None)
+                    .into_iter()
+                    .map(|address| address.to_address())
+                    .collect();
+                assert_eq!(page.as_slice(), *expected_users);
+            }
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            let status = sc.clear_whitelist(managed_buffer!(FIRST_BRAND_ID), OptionalValue::None);
+            assert_eq!(status, OperationCompletionStatus::Completed);
+        })
+        .assert_ok();
+
+    // the tier whitelist is kept
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let brand_id = managed_buffer!(FIRST_BRAND_ID);
+            assert_eq!(
+                sc.get_whitelist_size(brand_id.clone(), OptionalValue::None),
+                0
+            );
+            assert_eq!(
+                sc.get_whitelist_size(brand_id, OptionalValue::Some(managed_buffer!(gold_tier))),
+                1
+            );
+        })
+        .assert_ok();
+    check_whitelist_allowance(&mut nm_setup, &users[1], None, Some(0));
+    check_whitelist_allowance(&mut nm_setup, &users[0], Some(gold_tier), Some(1));
+}
+
+#[test]
+fn token_gate_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let gold_tier = FIRST_TIERS[0];
+    let holders_end = FIRST_MINT_START_TIMESTAMP + 1_000;
+
+    nm_setup.create_default_brands();
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"holders",
+                start_timestamp: FIRST_MINT_START_TIMESTAMP,
+                end_timestamp: holders_end,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[],
+            },
+        )
+        .assert_ok();
+    nm_setup
+        .call_add_mint_phase(
+            FIRST_BRAND_ID,
+            MintPhaseArgs {
+                name: b"public",
+                start_timestamp: holders_end,
+                end_timestamp: FIRST_MINT_END_TIMESTAMP,
+                whitelist_only: false,
+                max_nfts_per_wallet: 0,
+                tier_prices: &[],
+            },
+        )
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_phase_token_gate(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(b"presale"),
+                TokenIdentifier::from_esdt_bytes(GATE_TOKEN_ID),
+                true,
+            );
+        })
+        .assert_user_error("Invalid phase name");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_price_for_tier(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(gold_tier),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(2_000),
+            );
+        })
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_phase_token_gate(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(b"holders"),
+                TokenIdentifier::from_esdt_bytes(GATE_TOKEN_ID),
+                true,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .set_esdt_balance(&first_user_addr, USDC_TOKEN_ID, &rust_biguint!(100_000));
+    for nonce in 1..=2 {
+        nm_setup.b_mock.set_nft_balance(
+            &first_user_addr,
+            GATE_TOKEN_ID,
+            nonce,
+            &rust_biguint!(1),
+            &Vec::<u8>::new(),
+        );
+    }
+    nm_setup.b_mock.set_nft_balance(
+        &first_user_addr,
+        SECOND_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        &Vec::<u8>::new(),
+    );
+    nm_setup
+        .b_mock
+        .set_block_timestamp(FIRST_MINT_START_TIMESTAMP);
+
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            USDC_TOKEN_ID,
+            2_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_user_error("Must send a gate token along with the payment");
+    nm_setup
+        .call_buy_random_nft_with_gate(
+            &first_user_addr,
+            (SECOND_TOKEN_ID, 1),
+            (USDC_TOKEN_ID, 2_000),
+            FIRST_BRAND_ID,
+            gold_tier,
+        )
+        .assert_user_error("Invalid gate token");
+    nm_setup
+        .call_buy_random_nfts_multi_tier(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(gold_tier, 1)],
+            &[(USDC_TOKEN_ID, 2_000)],
+        )
+        .assert_user_error("Phase requires a gate token");
+
+    // the gate token is sent back
+    nm_setup
+        .call_buy_random_nft_with_gate(
+            &first_user_addr,
+            (GATE_TOKEN_ID, 1),
+            (USDC_TOKEN_ID, 2_000),
+            FIRST_BRAND_ID,
+            gold_tier,
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &first_user_addr,
+            GATE_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            None,
+        );
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &first_user_addr,
+            FIRST_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            None,
+        );
+
+    nm_setup
+        .call_buy_random_nft_with_gate(
+            &first_user_addr,
+            (GATE_TOKEN_ID, 1),
+            (USDC_TOKEN_ID, 2_000),
+            FIRST_BRAND_ID,
+            gold_tier,
+        )
+        .assert_user_error("Gate token already used");
+    nm_setup
+        .call_buy_random_nft_with_gate(
+            &first_user_addr,
+            (GATE_TOKEN_ID, 2),
+            (USDC_TOKEN_ID, 2_000),
+            FIRST_BRAND_ID,
+            gold_tier,
+        )
+        .assert_ok();
+
+    // no gate after the holders phase
+    nm_setup.b_mock.set_block_timestamp(holders_end);
+    nm_setup
+        .call_buy_random_nft(
+            &first_user_addr,
+            USDC_TOKEN_ID,
+            2_000,
+            FIRST_BRAND_ID,
+            gold_tier,
+            1,
+        )
+        .assert_ok();
+    nm_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        USDC_TOKEN_ID,
+        &rust_biguint!(100_000 - 3 * 2_000),
+    );
+}
+
+#[test]
+fn redemption_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let sc_addr = nm_setup.nm_wrapper.address_ref().clone();
+    let gold_tier = FIRST_TIERS[0];
+
+    nm_setup.create_default_brands();
+    for nonce in 1..=5 {
+        nm_setup.b_mock.set_nft_balance(
+            &first_user_addr,
+            OLD_COLLECTION_TOKEN_ID,
+            nonce,
+            &rust_biguint!(1),
+            &Vec::<u8>::new(),
+        );
+    }
+    nm_setup
+        .b_mock
+        .set_esdt_balance(&first_user_addr, USDC_TOKEN_ID, &rust_biguint!(2));
+
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(OLD_COLLECTION_TOKEN_ID, 1, 1)],
+        )
+        .assert_user_error("Brand has no redemption");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_redemption_config(
+                managed_buffer!(FIRST_BRAND_ID),
+                TokenIdentifier::from_esdt_bytes(FIRST_TOKEN_ID),
+                managed_buffer!(gold_tier),
+                2,
+                1,
+                true,
+            );
+        })
+        .assert_user_error("May not redeem the brand's own NFTs");
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_redemption_config(
+                managed_buffer!(FIRST_BRAND_ID),
+                TokenIdentifier::from_esdt_bytes(OLD_COLLECTION_TOKEN_ID),
+                managed_buffer!(gold_tier),
+                0,
+                1,
+                true,
+            );
+        })
+        .assert_user_error("Invalid exchange ratio");
+
+    // 2 old for 1 new, burning the old ones
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_redemption_config(
+                managed_buffer!(FIRST_BRAND_ID),
+                TokenIdentifier::from_esdt_bytes(OLD_COLLECTION_TOKEN_ID),
+                managed_buffer!(gold_tier),
+                2,
+                1,
+                true,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[
+                (OLD_COLLECTION_TOKEN_ID, 1, 1),
+                (OLD_COLLECTION_TOKEN_ID, 2, 1),
+            ],
+        )
+        .assert_user_error("NFT burn role not set");
+
+    nm_setup.b_mock.set_esdt_local_roles(
+        &sc_addr,
+        OLD_COLLECTION_TOKEN_ID,
+        &[EsdtLocalRole::NftBurn][..],
+    );
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(OLD_COLLECTION_TOKEN_ID, 1, 1)],
+        )
+        .assert_user_error("Invalid number of source NFTs");
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(OLD_COLLECTION_TOKEN_ID, 1, 1), (USDC_TOKEN_ID, 0, 1)],
+        )
+        .assert_user_error("Invalid payment");
+
+    // amounts that don't fit, or would overflow the total
+    nm_setup.b_mock.set_nft_balance(
+        &first_user_addr,
+        OLD_COLLECTION_TOKEN_ID,
+        6,
+        &rust_biguint!(u64::MAX),
+        &Vec::<u8>::new(),
+    );
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(OLD_COLLECTION_TOKEN_ID, 6, u64::MAX)],
+        )
+        .assert_user_error("Invalid payment amount");
+
+    let max_amount = i64::MAX as u64;
+    for nonce in 7..=9 {
+        nm_setup.b_mock.set_nft_balance(
+            &first_user_addr,
+            OLD_COLLECTION_TOKEN_ID,
+            nonce,
+            &rust_biguint!(max_amount),
+            &Vec::<u8>::new(),
+        );
+    }
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[
+                (OLD_COLLECTION_TOKEN_ID, 7, max_amount),
+                (OLD_COLLECTION_TOKEN_ID, 8, max_amount),
+                (OLD_COLLECTION_TOKEN_ID, 9, max_amount),
+            ],
+        )
+        .assert_user_error("Invalid number of source NFTs");
+
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[
+                (OLD_COLLECTION_TOKEN_ID, 1, 1),
+                (OLD_COLLECTION_TOKEN_ID, 2, 1),
+            ],
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &first_user_addr,
+            FIRST_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            None,
+        );
+    for nonce in 1..=2 {
+        nm_setup
+            .b_mock
+            .check_nft_balance::<ManagedBuffer<DebugApi>>(
+                &first_user_addr,
+                OLD_COLLECTION_TOKEN_ID,
+                nonce,
+                &rust_biguint!(0),
+                None,
+            );
+        nm_setup
+            .b_mock
+            .check_nft_balance::<ManagedBuffer<DebugApi>>(
+                &sc_addr,
+                OLD_COLLECTION_TOKEN_ID,
+                nonce,
+                &rust_biguint!(0),
+                None,
+            );
+    }
+
+    // 1 old for 2 new, locking the old ones
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_redemption_config(
+                managed_buffer!(FIRST_BRAND_ID),
+                TokenIdentifier::from_esdt_bytes(OLD_COLLECTION_TOKEN_ID),
+                managed_buffer!(gold_tier),
+                1,
+                2,
+                false,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[
+                (OLD_COLLECTION_TOKEN_ID, 3, 1),
+                (OLD_COLLECTION_TOKEN_ID, 4, 1),
+            ],
+        )
+        .assert_user_error("Max NFTs per transaction limit exceeded");
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(OLD_COLLECTION_TOKEN_ID, 3, 1)],
+        )
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &sc_addr,
+            OLD_COLLECTION_TOKEN_ID,
+            3,
+            &rust_biguint!(1),
+            None,
+        );
+    for nonce in 2..=3 {
+        nm_setup
+            .b_mock
+            .check_nft_balance::<ManagedBuffer<DebugApi>>(
+                &first_user_addr,
+                FIRST_TOKEN_ID,
+                nonce,
+                &rust_biguint!(1),
+                None,
+            );
+    }
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert_eq!(
+                sc.redeemed_source_nfts(&managed_buffer!(FIRST_BRAND_ID))
+                    .get(),
+                3
+            );
+            assert_eq!(
+                sc.redeemed_source_nfts_for_address(
+                    &managed_buffer!(FIRST_BRAND_ID),
+                    &managed_address!(&first_user_addr)
+                )
+                .get(),
+                3
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.clear_redemption_config(managed_buffer!(FIRST_BRAND_ID));
+        })
+        .assert_ok();
+    nm_setup
+        .call_redeem_nfts(
+            &first_user_addr,
+            FIRST_BRAND_ID,
+            &[(OLD_COLLECTION_TOKEN_ID, 4, 1)],
+        )
+        .assert_user_error("Brand has no redemption");
+}
+
+#[test]
+fn fusion_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let first_user_addr = nm_setup.first_user_address.clone();
+    let owner_addr = nm_setup.owner_address.clone();
+    let sc_addr = nm_setup.nm_wrapper.address_ref().clone();
+    let gold_tier = FIRST_TIERS[0];
+    let silver_tier = FIRST_TIERS[1];
+    let bronze_tier = FIRST_TIERS[2];
+
+    nm_setup.create_default_brands();
+
+    // nonces 1 to 4 are bronze, 5 is silver
+    nm_setup
+        .call_giveaway(
+            FIRST_BRAND_ID,
+            bronze_tier,
+            vec![(first_user_addr.clone(), 4)],
+        )
+        .assert_ok();
+    nm_setup
+        .call_giveaway(
+            FIRST_BRAND_ID,
+            silver_tier,
+            vec![(first_user_addr.clone(), 1)],
+        )
+        .assert_ok();
+    // minted before tiers were recorded
+    nm_setup.b_mock.set_nft_balance(
+        &first_user_addr,
+        FIRST_TOKEN_ID,
+        99,
+        &rust_biguint!(1),
+        &Vec::<u8>::new(),
+    );
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert_eq!(
+                sc.tier_for_nonce(&managed_buffer!(FIRST_BRAND_ID), 1).get(),
+                managed_buffer!(bronze_tier)
+            );
+            assert_eq!(
+                sc.tier_for_nonce(&managed_buffer!(FIRST_BRAND_ID), 5).get(),
+                managed_buffer!(silver_tier)
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_fuse_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1, 2])
+        .assert_user_error("No fusion recipe for tier");
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_fusion_recipe(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+                2,
+                managed_buffer!(bronze_tier),
+                true,
+            );
+        })
+        .assert_user_error("Fusion must output a different tier");
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_fusion_recipe(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+                2,
+                managed_buffer!(gold_tier),
+                true,
+            );
+        })
+        .assert_user_error("NFT burn role not set");
+
+    nm_setup.b_mock.set_esdt_local_roles(
+        &sc_addr,
+        FIRST_TOKEN_ID,
+        &[EsdtLocalRole::NftCreate, EsdtLocalRole::NftBurn][..],
+    );
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_fusion_recipe(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+                2,
+                managed_buffer!(gold_tier),
+                true,
+            );
+        })
+        .assert_ok();
+
+    nm_setup
+        .call_fuse_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1, 99])
+        .assert_user_error("Unknown tier for NFT");
+    nm_setup
+        .call_fuse_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1, 5])
+        .assert_user_error("All NFTs must be of the same tier");
+    nm_setup
+        .call_fuse_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1, 2, 3])
+        .assert_user_error("Invalid number of input NFTs");
+    nm_setup
+        .call_fuse_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[1, 2])
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &first_user_addr,
+            FIRST_TOKEN_ID,
+            6,
+            &rust_biguint!(1),
+            None,
+        );
+    for nonce in 1..=2 {
+        nm_setup
+            .b_mock
+            .check_nft_balance::<ManagedBuffer<DebugApi>>(
+                &sc_addr,
+                FIRST_TOKEN_ID,
+                nonce,
+                &rust_biguint!(0),
+                None,
+            );
+    }
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert_eq!(
+                sc.tier_for_nonce(&managed_buffer!(FIRST_BRAND_ID), 6).get(),
+                managed_buffer!(gold_tier)
+            );
+            assert!(sc
+                .tier_for_nonce(&managed_buffer!(FIRST_BRAND_ID), 1)
+                .is_empty());
+            assert_eq!(
+                sc.get_available_nfts(
+                    &managed_buffer!(FIRST_BRAND_ID),
+                    &managed_buffer!(gold_tier)
+                ),
+                FIRST_NFT_AMOUNTS[0] - 1
+            );
+        })
+        .assert_ok();
+
+    // locked instead of burned
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_fusion_recipe(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+                2,
+                managed_buffer!(silver_tier),
+                false,
+            );
+        })
+        .assert_ok();
+    nm_setup
+        .call_fuse_nfts(&first_user_addr, FIRST_BRAND_ID, FIRST_TOKEN_ID, &[3, 4])
+        .assert_ok();
+    for nonce in 3..=4 {
+        nm_setup
+            .b_mock
+            .check_nft_balance::<ManagedBuffer<DebugApi>>(
+                &sc_addr,
+                FIRST_TOKEN_ID,
+                nonce,
+                &rust_biguint!(1),
+                None,
+            );
+    }
+    nm_setup
+        .b_mock
+        .check_nft_balance::<ManagedBuffer<DebugApi>>(
+            &first_user_addr,
+            FIRST_TOKEN_ID,
+            7,
+            &rust_biguint!(1),
+            None,
+        );
+
+    nm_setup
+        .b_mock
+        .execute_tx(&owner_addr, &nm_setup.nm_wrapper, &rust_biguint!(0), |sc| {
+            sc.remove_fusion_recipe(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(bronze_tier),
+            );
+        })
+        .assert_ok();
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            assert!(sc
+                .fusion_recipes(&managed_buffer!(FIRST_BRAND_ID))
+                .is_empty());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn giveaway_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    nm_setup.create_default_brands();
+
+    let first_tier = SECOND_TIERS[0];
+
+    // giveaway single nft
+    let first_user_addr = nm_setup.first_user_address.clone();
+    nm_setup
+        .call_giveaway(
+            SECOND_BRAND_ID,
+            first_tier,
+            [(first_user_addr.clone(), 1)].to_vec(),
+        )
+        .assert_ok();
+
+    // user received nonce 1 and ID 7
+    let mut attr = nm_setup.build_nft_attributes_second_token(7);
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        SECOND_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        Some(&attr),
+    );
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let mapper = sc.available_ids(
+                &managed_buffer!(SECOND_BRAND_ID),
+                &managed_buffer!(first_tier),
+            );
+            assert_eq!(mapper.len(), 9);
+            assert_eq!(mapper.get(1), 1);
+            assert_eq!(mapper.get(2), 2);
+            assert_eq!(mapper.get(3), 3);
+            assert_eq!(mapper.get(4), 4);
+            assert_eq!(mapper.get(5), 5);
+            assert_eq!(mapper.get(6), 6);
+            assert_eq!(mapper.get(7), 10); // this changed
+            assert_eq!(mapper.get(8), 8);
+            assert_eq!(mapper.get(9), 9);
+        })
+        .assert_ok();
+
+    // giveaway two, single user
+    nm_setup
+        .call_giveaway(
+            SECOND_BRAND_ID,
+            first_tier,
+            [(first_user_addr.clone(), 2)].to_vec(),
+        )
+        .assert_ok();
+
+    // received ID 4 and 5
+    attr = nm_setup.build_nft_attributes_second_token(4);
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        SECOND_TOKEN_ID,
+        2,
+        &rust_biguint!(1),
+        Some(&attr),
+    );
+
+    attr = nm_setup.build_nft_attributes_second_token(5);
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        SECOND_TOKEN_ID,
+        3,
+        &rust_biguint!(1),
+        Some(&attr),
+    );
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let mapper = sc.available_ids(
+                &managed_buffer!(SECOND_BRAND_ID),
+                &managed_buffer!(first_tier),
+            );
+            assert_eq!(mapper.len(), 7);
+            assert_eq!(mapper.get(1), 1);
+            assert_eq!(mapper.get(2), 2);
+            assert_eq!(mapper.get(3), 3);
+            assert_eq!(mapper.get(4), 9); // pos 4
+            assert_eq!(mapper.get(5), 8); // and 5 changed
+            assert_eq!(mapper.get(6), 6);
+            assert_eq!(mapper.get(7), 10);
+        })
+        .assert_ok();
+
+    // giveaway, multiple users
+    let second_user_addr = nm_setup.second_user_address.clone();
+    nm_setup
+        .call_giveaway(
+            SECOND_BRAND_ID,
+            first_tier,
+            [(first_user_addr.clone(), 2), (second_user_addr.clone(), 1)].to_vec(),
+        )
+        .assert_ok();
+
+    // user 1 received IDs 10 and 1
+    attr = nm_setup.build_nft_attributes_second_token(10);
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        SECOND_TOKEN_ID,
+        4,
+        &rust_biguint!(1),
+        Some(&attr),
+    );
+
+    attr = nm_setup.build_nft_attributes_second_token(1);
+    nm_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        SECOND_TOKEN_ID,
+        5,
+        &rust_biguint!(1),
+        Some(&attr),
+    );
+
+    // user 2 received ID
+    attr = nm_setup.build_nft_attributes_second_token(6);
+    nm_setup.b_mock.check_nft_balance(
+        &second_user_addr,
+        SECOND_TOKEN_ID,
+        6,
+        &rust_biguint!(1),
+        Some(&attr),
+    );
+
+    // mapper progress:
+    // 1 2 3 9 8 6 10
+    // 1 2 3 9 8 6
+    // 6 2 3 9 8
+    // 8 2 3 9
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let mapper = sc.available_ids(
+                &managed_buffer!(SECOND_BRAND_ID),
+                &managed_buffer!(first_tier),
+            );
+            assert_eq!(mapper.len(), 4);
+            assert_eq!(mapper.get(1), 8);
+            assert_eq!(mapper.get(2), 2);
+            assert_eq!(mapper.get(3), 3);
+            assert_eq!(mapper.get(4), 9);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn formatters_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    nm_setup.create_default_brands();
+
+    // test NFT attributes
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let uri_templates = sc.get_uri_templates(&managed_buffer!(FIRST_BRAND_ID));
+            let attributes = sc.build_nft_attributes(
+                &uri_templates,
+                &managed_buffer!(FIRST_COLLECTION_HASH),
+                &managed_buffer!(FIRST_BRAND_ID),
+                2,
+            );
+
+            let expected_attributes =
+                "metadata:QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/2.json;tags:funny,sad,memes";
+            assert_eq!(managed_buffer_to_string(&attributes), expected_attributes.to_string());
+        })
+        .assert_ok();
+
+    // test generated URIs
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let uri_templates = sc.get_uri_templates(&managed_buffer!(FIRST_BRAND_ID));
+            let coll_hash = managed_buffer!(FIRST_COLLECTION_HASH);
+            let nft_main_file_uri = sc.build_nft_main_file_uri(
+                &uri_templates,
+                &coll_hash,
+                2,
+                &managed_buffer!(b"jpg"),
+            );
+            let expected_main_file_uri =
+                "https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/2.jpg";
+            assert_eq!(
+                managed_buffer_to_string(&nft_main_file_uri),
+                expected_main_file_uri.to_string()
+            );
+
+            let nft_json_file_uri = sc.build_nft_json_file_uri(&uri_templates, &coll_hash, 2);
+            let expected_nft_json_uri =
+                "https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/2.json";
+            assert_eq!(
+                managed_buffer_to_string(&nft_json_file_uri),
+                expected_nft_json_uri.to_string()
+            );
+
+            let collection_json_uri = sc.build_collection_json_file_uri(&uri_templates, &coll_hash);
+            let expected_collection_json_uri = "https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/collection.json";
+            assert_eq!(
+                managed_buffer_to_string(&collection_json_uri),
+                expected_collection_json_uri.to_string()
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn custom_test() {
+    let mut nm_setup = NftMinterSetup::new(nft_minter::contract_obj);
+    let mut b_mock = BlockchainStateWrapper::new();
+    let owner_address = b_mock.create_user_account(&rust_biguint!(OWNER_EGLD_BALANCE));
+    
+    // check contract storage after init()
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let mapper = sc.collections_category();
+            assert_eq!(mapper.get(), managed_buffer!(CATEGORY));
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let mapper = sc.royalties_claim_address();
+            assert_eq!(mapper.get(), managed_address!(&owner_address));
+        })
+        .assert_ok();
+
+    nm_setup
+        .b_mock
         .execute_query(&nm_setup.nm_wrapper, |sc| {
             let mapper = sc.mint_payments_claim_address();
             assert_eq!(mapper.get(), managed_address!(&owner_address));
@@ -813,10 +5542,7 @@ fn custom_test() {
         .execute_query(&nm_setup.nm_wrapper, |sc| {
             let mapper = sc.registered_collection_hashes();
 
-            let collection_hash = 
-                ManagedByteArray::<DebugApi, COLLECTION_HASH_LEN>::new_from_bytes(
-                    CUSTOM_COLLECTION_HASH
-                );
+            let collection_hash = managed_buffer!(CUSTOM_COLLECTION_HASH);
             assert_eq!(mapper.contains(&collection_hash), true);
         })
         .assert_ok();
@@ -846,9 +5572,7 @@ fn custom_test() {
             assert_eq!(result.nft_token_id, expected_token_id.unwrap_esdt());
 
             let expected_brand_info = BrandInfo::<DebugApi> {
-                collection_hash: ManagedByteArray::<DebugApi, COLLECTION_HASH_LEN>::new_from_bytes(
-                    CUSTOM_COLLECTION_HASH,
-                ),
+                collection_hash: managed_buffer!(CUSTOM_COLLECTION_HASH),
                 token_display_name: managed_buffer!(CUSTOM_TOKEN_DISPLAY_NAME),
                 media_type: managed_buffer!(CUSTOM_MEDIA_TYPE),
                 royalties: managed_biguint!(CUSTOM_ROYALTIES),
@@ -857,6 +5581,7 @@ fn custom_test() {
                     end: CUSTOM_MINT_END_TIMESTAMP,
                 },
                 whitelist_expire_timestamp: CUSTOM_WHITELIST_EXPIRE_TIMESTAMP,
+                is_revealed: true,
             };
             assert_eq!(result.brand_info, expected_brand_info);
 
@@ -870,6 +5595,11 @@ fn custom_test() {
                         token_id: managed_token_id!(CUSTOM_MINT_PRICE_TOKEN_ID),
                         amount: managed_biguint!(CUSTOM_MINT_PRICE_AMOUNT),
                     },
+                    price_options: ManagedVec::from_single_item(MintPrice::<DebugApi> {
+                        token_id: managed_token_id!(CUSTOM_MINT_PRICE_TOKEN_ID),
+                        amount: managed_biguint!(CUSTOM_MINT_PRICE_AMOUNT),
+                    }),
+                    is_paused: false,
                 });
             }
             assert_eq!(
@@ -881,6 +5611,69 @@ fn custom_test() {
 
 }
 
+fn check_current_price<NftMinterObjBuilder>(
+    nm_setup: &mut NftMinterSetup<NftMinterObjBuilder>,
+    tier: &[u8],
+    expected_price: u64,
+) where
+    NftMinterObjBuilder: 'static + Copy + Fn() -> nft_minter::ContractObj<DebugApi>,
+{
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let price =
+                sc.get_current_price_view(managed_buffer!(FIRST_BRAND_ID), managed_buffer!(tier));
+            assert_eq!(price.amount, managed_biguint!(expected_price));
+        })
+        .assert_ok();
+}
+
+fn check_price_quote<NftMinterObjBuilder>(
+    nm_setup: &mut NftMinterSetup<NftMinterObjBuilder>,
+    tier: &[u8],
+    nfts_to_buy: usize,
+    expected_price: u64,
+) where
+    NftMinterObjBuilder: 'static + Copy + Fn() -> nft_minter::ContractObj<DebugApi>,
+{
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let price = sc.get_price_quote_view(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_buffer!(tier),
+                nfts_to_buy,
+            );
+            assert_eq!(price.amount, managed_biguint!(expected_price));
+        })
+        .assert_ok();
+}
+
+fn check_whitelist_allowance<NftMinterObjBuilder>(
+    nm_setup: &mut NftMinterSetup<NftMinterObjBuilder>,
+    address: &Address,
+    opt_tier: Option<&[u8]>,
+    expected_allowance: Option<usize>,
+) where
+    NftMinterObjBuilder: 'static + Copy + Fn() -> nft_minter::ContractObj<DebugApi>,
+{
+    nm_setup
+        .b_mock
+        .execute_query(&nm_setup.nm_wrapper, |sc| {
+            let opt_tier = match opt_tier {
+                Some(tier) => OptionalValue::Some(managed_buffer!(tier)),
+                None => OptionalValue::None,
+            };
+            let allowance = sc.get_whitelist_allowance(
+                managed_buffer!(FIRST_BRAND_ID),
+                managed_address!(address),
+                opt_tier,
+            );
+            assert_eq!(allowance, expected_allowance);
+        })
+        .assert_ok();
+}
+
 fn managed_buffer_to_string(buffer: &ManagedBuffer<DebugApi>) -> String {
     String::from_utf8(buffer.to_boxed_bytes().into_vec()).unwrap()
 }
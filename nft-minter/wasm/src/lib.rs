@@ -8,35 +8,133 @@ elrond_wasm_node::wasm_endpoints! {
     nft_minter
     (
         callBack
+        addAllowedScRecipients
+        addMintPhase
+        addTierToBrand
+        addToTierWhitelist
         addToWhitelist
         addUserToAdminList
+        buyMysteryTierNft
         buyRandomNft
+        buyRandomNftFor
+        buyRandomNftWithVoucher
+        buyRandomNftsMultiTier
+        claimAuctionRebate
         claimMintPayments
         claimRoyalties
         claimRoyaltiesFromMarketplace
+        clearMintPhases
+        clearMintVoucherSigner
+        clearMysteryTier
+        clearPendingBrandCreation
+        clearPhaseTokenGate
+        clearRedemptionConfig
+        clearUriTemplatesForBrand
+        clearWhitelist
+        clearWhitelistMerkleRoot
+        closeMint
+        confirmTokenRoles
+        extendTier
+        fuseNfts
         getAccumulatedMintPayments
         getAccumulatedRoyalties
         getAllBrandsInfo
+        getAllowedScRecipients
+        getAuctionBought
+        getAuctionClearingPrice
+        getAuctionPaid
+        getBrandIdForToken
         getBrandInfo
         getCollectionsCategory
+        getCurrentPrice
+        getDefaultUriTemplates
+        getExtraPriceOptionsForTier
+        getFusionRecipes
+        getGiftLimitsParty
         getMaxNftsPerTransaction
+        getMaxNftsPerWallet
+        getMaxNftsPerWalletForTier
+        getMerkleWhitelistMinted
         getMintPaymentsClaimAddress
+        getMintPhases
+        getMintVoucherSigner
         getMintWhitelist
+        getMintWhitelistPage
+        getMintedCountForAddress
+        getMintedCountForAddressInTier
+        getMintedInPhase
+        getMysteryTierOdds
+        getMysteryTierPrice
         getNftTiersForBrand
         getNftTokenIdForBrand
+        getPhasePriceForTier
+        getPhaseTokenGate
         getPriceForTier
+        getPriceQuote
+        getPricingStrategy
+        getRedeemedSourceNfts
+        getRedeemedSourceNftsForAddress
+        getRedemptionConfig
         getRegisterdCollectionHashes
         getRegisteredBrands
         getRoyaltiesClaimAddress
         getTagsForBrand
+        getTierExtensions
+        getTierForNonce
+        getTierMintWhitelist
+        getUnrevealedNftId
+        getUriTemplatesForBrand
+        getWhitelistAllowance
+        getWhitelistMerkleRoot
+        getWhitelistSize
         giveawayNfts
+        isBrandPaused
+        isContractPaused
+        isGateNonceUsed
+        isMintClosed
+        isMintVoucherUsed
+        isTierPaused
+        isWhitelisted
         issueTokenForBrand
+        needsTokenRoleConfirmation
         nftIdOffsetForTier
+        pauseBrand
+        pauseContract
+        pauseTier
+        redeemNfts
+        registerBrandWithExistingToken
+        removeAllowedScRecipients
+        removeFromTierWhitelist
         removeFromWhitelist
+        removeFusionRecipe
         removeUserFromAdminList
+        revealBrand
+        revealNfts
+        setDefaultUriTemplates
+        setExtraPriceOptionsForTier
+        setFusionRecipe
+        setGiftLimitsParty
         setMaxNftsPerTransaction
+        setMaxNftsPerWallet
+        setMaxNftsPerWalletForTier
         setMintPaymentsClaimAddress
+        setMintPeriod
+        setMintVoucherSigner
         setMintWhitelistExpireTimestamp
+        setMysteryTier
+        setPhaseTokenGate
+        setPriceForTier
+        setPricingStrategy
+        setRedemptionConfig
         setRoyaltiesClaimAddress
+        setRoyaltiesForBrand
+        setTagsForBrand
+        setTokenDisplayName
+        setUriTemplatesForBrand
+        setWhitelistMerkleRoot
+        settleAuction
+        unpauseBrand
+        unpauseContract
+        unpauseTier
     )
 }
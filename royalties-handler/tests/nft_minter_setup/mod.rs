@@ -1,5 +1,5 @@
 use super::constants::*;
-use elrond_wasm::types::{Address, EsdtLocalRole, ManagedVec, MultiValueEncoded};
+use elrond_wasm::types::{Address, EsdtLocalRole, ManagedBuffer, ManagedVec, MultiValueEncoded};
 use elrond_wasm_debug::{
     managed_address, managed_biguint, managed_buffer, rust_biguint,
     testing_framework::{BlockchainStateWrapper, ContractObjWrapper},
@@ -7,7 +7,6 @@ use elrond_wasm_debug::{
     DebugApi,
 };
 use nft_minter::brand_creation::BrandCreationModule;
-use nft_minter::nft_attributes_builder::COLLECTION_HASH_LEN;
 use nft_minter::royalties::RoyaltiesModule;
 use nft_minter::NftMinter;
 
@@ -162,7 +161,7 @@ where
 {
     pub fn call_create_new_brand(
         &mut self,
-        collection_hash: &[u8; COLLECTION_HASH_LEN],
+        collection_hash: &[u8],
         brand_id: &[u8],
         media_type: &[u8],
         royalties: u64,
@@ -197,13 +196,15 @@ where
                             managed_buffer!(tier.clone()),
                             *nr_nfts,
                             managed_biguint!(mint_price_amount),
+                            0,
+                            ManagedVec::new(),
                         )
                             .into(),
                     );
                 }
 
                 sc.issue_token_for_brand(
-                    collection_hash.into(),
+                    managed_buffer!(collection_hash),
                     managed_buffer!(brand_id),
                     managed_buffer!(media_type),
                     managed_biguint!(royalties),
@@ -213,6 +214,10 @@ where
                     managed_buffer!(token_display_name),
                     managed_buffer!(token_ticker),
                     0,
+                    0,
+                    false,
+                    ManagedBuffer::new(),
+                    ManagedBuffer::new(),
                     managed_tags,
                     tier_args,
                 );